@@ -0,0 +1,47 @@
+//! Compares grouping `FIPSCode`s in a `FIPSMap` against a `HashMap` with the default hasher. Run with
+//! `cargo bench --features fast_hash`.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use ixa_fips::{collections::FIPSMap, FIPSCode, USState};
+use std::{collections::HashMap, hint::black_box, time::Instant};
+
+const RECORD_COUNT: usize = 5_000_000;
+
+/// A synthetic stream of home codes spread over many tracts in a few counties.
+fn homes() -> Vec<FIPSCode> {
+    (0..RECORD_COUNT)
+        .map(|i| {
+            let county = 1 + (i % 7) as u16;
+            let tract = 100 + (i / 7 % 2_000) as u32;
+            let id = 1 + (i % 3_000) as u16;
+            FIPSCode::new(USState::TX.into(), county, tract, 1, id, 0).unwrap()
+        })
+        .collect()
+}
+
+/// Counts the records per tract and returns the elapsed time in milliseconds.
+fn time_grouping<M: Default>(codes: &[FIPSCode], mut count: impl FnMut(&mut M, FIPSCode)) -> u128 {
+    let start = Instant::now();
+    let mut map = M::default();
+    for &code in codes {
+        count(&mut map, code.parent_tract());
+    }
+    black_box(map);
+    start.elapsed().as_millis()
+}
+
+fn main() {
+    let codes = homes();
+
+    let sip = time_grouping::<HashMap<FIPSCode, usize>>(&codes, |map, code| {
+        *map.entry(code).or_default() += 1;
+    });
+    let fast = time_grouping::<FIPSMap<usize>>(&codes, |map, code| {
+        *map.entry(code).or_default() += 1;
+    });
+
+    println!("Grouping {RECORD_COUNT} records by tract:");
+    println!("  HashMap (default): {sip} ms");
+    println!("  FIPSMap:           {fast} ms");
+}
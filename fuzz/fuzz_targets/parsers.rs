@@ -0,0 +1,56 @@
+//! Feeds arbitrary input to the parsers and checks that they never panic and that every result is well formed: the
+//! unparsed remainder, whether of a success or an error, is a suffix of the input, and each error is consistent with its
+//! documented meaning. Run with `cargo fuzz run parsers` from the repository root.
+
+#![no_main]
+
+use ixa_aspr::{
+    fips::parser::{parse_census_api_geoid, parse_decimal_digits_to_bits},
+    parser::{FIPSParseResult, FIPSParserError, parse_fips_id, parse_integer},
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The first two bytes are the digit and bit counts for `parse_decimal_digits_to_bits`; the rest is the input.
+    let Some((&[digit_count, bit_count], rest)) = data.split_first_chunk::<2>() else {
+        return;
+    };
+    let Ok(input) = std::str::from_utf8(rest) else {
+        return;
+    };
+
+    let result = parse_decimal_digits_to_bits(digit_count.into(), bit_count, input);
+    if let Ok((_, value)) = result {
+        assert!(bit_count >= 64 || value < 1u64 << bit_count);
+    }
+    check(input, result);
+    check(input, parse_integer(input));
+    check(input, parse_fips_id(input));
+    check(input, parse_census_api_geoid(input));
+});
+
+/// Checks that `result`, returned by a parser given `input`, is well formed.
+fn check<T>(input: &str, result: FIPSParseResult<T>) {
+    let rest = match result {
+        Ok((rest, _)) => rest,
+        Err((rest, error)) => {
+            match error {
+                FIPSParserError::Empty => assert!(input.trim().is_empty()),
+                FIPSParserError::InvalidDigit { found } => {
+                    assert!(!found.is_ascii_digit() && input.contains(found));
+                }
+                FIPSParserError::ValueExceedsCapacity { value, capacity } => {
+                    assert!(value > capacity)
+                }
+                // A `u64` holds any value of up to 19 digits.
+                FIPSParserError::Overflow { found } => assert!(found >= 20),
+                FIPSParserError::InvalidLength { .. }
+                | FIPSParserError::Zero
+                | FIPSParserError::Ambiguous { .. }
+                | FIPSParserError::UnknownPrefix => {}
+            }
+            rest
+        }
+    };
+    assert!(input.ends_with(rest));
+}
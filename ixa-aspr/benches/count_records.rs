@@ -0,0 +1,52 @@
+//! Compares `count_records` against counting the records of an `ASPRRecordIterator` on a generated file the size of the
+//! WY state population. Run with `cargo bench -p ixa-aspr`.
+
+use ixa_aspr::archive::{count_records, set_aspr_data_path, ASPRRecordIterator};
+use std::{
+    fmt::Write as _,
+    fs,
+    hint::black_box,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+const RECORD_COUNT: usize = 583_200;
+const FILE_NAME: &str = "count_records_bench.csv";
+
+/// Writes a synthetic data file of `RECORD_COUNT` records to `directory`.
+fn write_records(directory: &Path) {
+    let mut contents = String::from("age,homeId,schoolId,workplaceId\n");
+    for i in 0..RECORD_COUNT {
+        let tract = 100 + i / 1_000 % 1_000;
+        let home = 1 + i % 1_000;
+        writeln!(contents, "{},56021{:06}{:04},,", i % 90, tract, home).unwrap();
+    }
+    fs::write(directory.join(FILE_NAME), contents).unwrap();
+}
+
+/// Returns the count and the elapsed time in milliseconds.
+fn time_count(count: impl FnOnce() -> usize) -> (usize, u128) {
+    let start = Instant::now();
+    let count = black_box(count());
+    (count, start.elapsed().as_millis())
+}
+
+fn main() {
+    let directory = std::env::temp_dir();
+    write_records(&directory);
+    set_aspr_data_path(directory.clone());
+
+    let (parsed, parse_ms) = time_count(|| {
+        ASPRRecordIterator::from_path(PathBuf::from(FILE_NAME))
+            .unwrap()
+            .count()
+    });
+    let (scanned, scan_ms) = time_count(|| count_records(PathBuf::from(FILE_NAME)).unwrap());
+    assert_eq!(parsed, scanned);
+
+    println!("Counting {RECORD_COUNT} records:");
+    println!("  ASPRRecordIterator: {parse_ms} ms");
+    println!("  count_records:      {scan_ms} ms");
+
+    fs::remove_file(directory.join(FILE_NAME)).unwrap();
+}
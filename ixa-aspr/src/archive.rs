@@ -16,7 +16,8 @@ println!("The new ASPR data path: {:?}", new_path);
 
 You can set the ASPR data path to a zip archive or to a directory. You can refer to subdirectories of the data path
 with the `ALL_STATES_DIR`, `CBSA_ALL_DIR`, `CBSA_ONLY_RESIDENTS_DIR`, `NON_CBSA_RESIDENTS_DIR`, and `MULTI_STATE_DIR`
-constants for convenience. (These are `&str`s.)
+constants for convenience. (These are `&str`s.) The `available_subdirectories` function lists the subdirectories
+actually present in one of the top-level layouts enumerated by `AsprLayout`.
 
 You can iterate over the records in a CSV file under the ASPR data path with the `ASPRRecordIterator` struct. This
 struct transparently handles the case that the ASPR data path is a zip archive or a directory for you. Just provide the
@@ -49,26 +50,37 @@ let records = ASPRRecordIterator::from_file_iterator(iter_csv_files(ALL_STATES_D
 // Do something with the records...
 ```
 
+To size a file, prefer `count_records` to counting the records of an `ASPRRecordIterator`. It scans the file for line
+breaks without parsing any records, which is much faster for large files:
+
+```ignore
+# use ixa_aspr::archive::{count_records, ALL_STATES_DIR};
+# use std::path::PathBuf;
+let record_count = count_records(PathBuf::from(ALL_STATES_DIR).join("wy.csv")).unwrap();
+```
+
 */
 
 use crate::{
     errors::ASPRError,
-    parser::{
-        parse_fips_home_id,
-        parse_fips_school_id,
-        parse_fips_workplace_id
-    },
-    ASPRPersonRecord,
+    parser::{parse_fips_home_id, parse_fips_school_id, parse_fips_workplace_id, parse_integer},
+    ASPRPersonRecord, ASPRPersonRecordAnnotated, ASPRPersonRecordRaw,
+};
+use ixa_fips::{
+    parser::{FIPSParseResult, FIPSParserError},
+    states::USState,
+    FIPSCode,
 };
-use ixa_fips::states::USState;
+use memchr::memchr_iter;
 use once_cell::sync::Lazy;
 use ouroboros::self_referencing;
 use zip::{read::ZipFile, ZipArchive};
 use std::{
+    collections::{BTreeMap, HashMap, HashSet},
     fs::File,
     io::Lines,
-    io::{BufRead, BufReader},
-    path::PathBuf,
+    io::{BufRead, BufReader, ErrorKind},
+    path::{Path, PathBuf},
     sync::RwLock,
 };
 
@@ -80,6 +92,63 @@ pub const CBSA_ONLY_RESIDENTS_DIR: &str = "cbsa_only_residents";
 pub const NON_CBSA_RESIDENTS_DIR: &str = "non_CBSA_residents";
 pub const MULTI_STATE_DIR: &str = "Multi-state";
 
+/// The top-level directories of the ASPR data, each of which holds a complete copy of the population laid out
+/// differently.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum AsprLayout {
+    /// `ALL_STATES_DIR`: one file per state.
+    AllStates,
+    /// `CBSA_ALL_DIR`: one file per CBSA, including people who work or go to school but do not live in the CBSA.
+    CbsaAll,
+    /// `CBSA_ONLY_RESIDENTS_DIR`: one file per CBSA, including only residents of the CBSA.
+    CbsaOnlyResidents,
+}
+
+impl AsprLayout {
+    /// Returns the name of the directory of this layout relative to the ASPR data path.
+    #[must_use]
+    pub fn dir(&self) -> &'static str {
+        match self {
+            AsprLayout::AllStates => ALL_STATES_DIR,
+            AsprLayout::CbsaAll => CBSA_ALL_DIR,
+            AsprLayout::CbsaOnlyResidents => CBSA_ONLY_RESIDENTS_DIR,
+        }
+    }
+}
+
+/// The kind of a data file in one of the CBSA layouts, determined by the subdirectory of the layout directory it is in.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CbsaKind {
+    /// A file in a per-state subdirectory, e.g. `AK`, holding a CBSA that lies within a single state.
+    SingleState,
+    /// A file in `MULTI_STATE_DIR`, holding a CBSA that spans several states.
+    MultiState,
+    /// A file in `NON_CBSA_RESIDENTS_DIR`, holding the people of a state who do not live in any CBSA.
+    NonCbsaResidents,
+}
+
+/// Classifies the data file at `path` by the subdirectory of `CBSA_ALL_DIR` or `CBSA_ONLY_RESIDENTS_DIR` it is in, so
+/// that a generic loader can route files discovered with `iter_csv_files`. `path` may be relative to the ASPR data path
+/// or include it. Returns `None` if `path` is not a file in a subdirectory of a CBSA layout directory, e.g. a file in
+/// `ALL_STATES_DIR`.
+#[must_use]
+pub fn classify_cbsa_path(path: &Path) -> Option<CbsaKind> {
+    let mut components = path.components().map(std::path::Component::as_os_str);
+    components
+        .find(|&component| component == CBSA_ALL_DIR || component == CBSA_ONLY_RESIDENTS_DIR)?;
+    let subdirectory = components.next()?;
+    // The subdirectory must contain the file rather than be the file.
+    components.next()?;
+
+    Some(if subdirectory == MULTI_STATE_DIR {
+        CbsaKind::MultiState
+    } else if subdirectory == NON_CBSA_RESIDENTS_DIR {
+        CbsaKind::NonCbsaResidents
+    } else {
+        CbsaKind::SingleState
+    })
+}
+
 // Path to the ASPR data directory
 const DEFAULT_ASPR_DATA_PATH: &str = "../../CDC/data/ASPR_Synthetic_Population";
 // ToDo: Get the ASPR data path from an environment variable.
@@ -159,6 +228,13 @@ impl Iterator for ZipLineIterator {
 }
 // endregion ZipLineIterator
 
+/// Returns `true` if the ASPR data path `path` is a zip archive rather than a directory, judging by its extension.
+fn is_zip_archive_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
 /// Interface abstracting over the different ways to iterate over lines in an ASPR data file.
 enum LineIterator {
     File(Lines<BufReader<File>>),
@@ -169,13 +245,7 @@ impl LineIterator {
     pub fn from_path(file_path: PathBuf) -> Result<Self, ASPRError> {
         let path = get_aspr_data_path();
 
-        // The dance to check if the path is a zip archive is ridiculous.
-        if path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .map(|s| s.eq_ignore_ascii_case("zip"))
-            .unwrap_or(false)
-        {
+        if is_zip_archive_path(&path) {
             // The path is a zip archive.
             Ok(LineIterator::Zip(ZipLineIterator::from_path(
                 path, file_path,
@@ -204,17 +274,12 @@ impl Iterator for LineIterator {
 /// Returns an iterator over all the data files in the given subdirectory of the ASPR data path. The ASPR data path can
 /// be a zip archive or a directory. The paths returned are relative to the ASPR data path.
 pub fn iter_csv_files(
-    subdirectory: &'static str,
+    subdirectory: impl AsRef<Path>,
 ) -> Result<std::vec::IntoIter<PathBuf>, ASPRError> {
+    let subdirectory = subdirectory.as_ref();
     let mut path = get_aspr_data_path();
 
-    // The dance to check if the path is a zip archive is ridiculous.
-    if path
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .map(|s| s.eq_ignore_ascii_case("zip"))
-        .unwrap_or(false)
-    {
+    if is_zip_archive_path(&path) {
         // Iterator through files within the zip archive.
         let file = File::open(path).map_err(ASPRError::Io)?;
         let reader = BufReader::new(file);
@@ -223,7 +288,7 @@ pub fn iter_csv_files(
         let file_names: Vec<PathBuf> = archive
             .file_names()
             .filter_map(|s| {
-                if s.starts_with(subdirectory) {
+                if Path::new(s).starts_with(subdirectory) {
                     Some(PathBuf::from(s))
                 } else {
                     None
@@ -250,9 +315,241 @@ pub fn iter_csv_files(
     }
 }
 
+/// Returns the names of the subdirectories of the directory of `layout` that are actually present under the ASPR data
+/// path, sorted, e.g. `["AK", "Multi-state", "TX", "non_CBSA_residents"]` for `AsprLayout::CbsaAll`. The ASPR data path
+/// can be a zip archive or a directory.
+pub fn available_subdirectories(layout: AsprLayout) -> Result<Vec<String>, ASPRError> {
+    let path = get_aspr_data_path();
+    let layout_dir = Path::new(layout.dir());
+    let mut subdirectories = vec![];
+
+    if is_zip_archive_path(&path) {
+        // A zip archive has no directories as such, so collect the first path component below the layout directory of
+        // every file that has one.
+        let file = File::open(path).map_err(ASPRError::Io)?;
+        let reader = BufReader::new(file);
+        let archive = ZipArchive::new(reader).map_err(ASPRError::ZipError)?;
+
+        for file_name in archive.file_names() {
+            let Ok(relative) = Path::new(file_name).strip_prefix(layout_dir) else {
+                continue;
+            };
+            let mut components = relative.components();
+            if let (Some(first), Some(_)) = (components.next(), components.next()) {
+                subdirectories.push(first.as_os_str().to_string_lossy().into_owned());
+            }
+        }
+    } else {
+        for entry in path.join(layout_dir).read_dir().map_err(ASPRError::Io)? {
+            let entry = entry.map_err(ASPRError::Io)?;
+            if entry.path().is_dir() {
+                subdirectories.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    subdirectories.sort_unstable();
+    subdirectories.dedup();
+    Ok(subdirectories)
+}
+
+/// The number of columns in an ASPR data file: age, home id, school id, and workplace id.
+pub const ASPR_COLUMN_COUNT: usize = 4;
+
+/// Strips a leading UTF-8 byte order mark and any trailing whitespace (including `\r`) from a header line.
+fn normalize_header(line: &str) -> &str {
+    line.strip_prefix('\u{feff}').unwrap_or(line).trim_end()
+}
+
+/// Detects the field delimiter of a data file from its header row. ASPR data files are comma-separated, but some
+/// derived exports are tab-separated.
+fn detect_delimiter(header: &str) -> char {
+    if header.contains('\t') && !header.contains(',') {
+        '\t'
+    } else {
+        ','
+    }
+}
+
+/// Returns `true` if `line`, the first line of a data file, is a header row rather than a data row, that is, if its first
+/// field is not a number. (An out-of-range age is still a number, so it is not mistaken for a header.)
+fn is_header_row(line: &str, delimiter: char) -> bool {
+    line.split(delimiter).next().is_none_or(|age| {
+        let age = age.trim();
+        age.is_empty() || !age.bytes().all(|c| c.is_ascii_digit())
+    })
+}
+
+/// How `ASPRRecordIterator` treats an age that does not fit in a `u8`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default, Debug)]
+pub enum AgePolicy {
+    /// Report the age as an `ASPRError::Parse` error.
+    #[default]
+    Reject,
+    /// Clamp the age to `u8::MAX`.
+    Clamp,
+}
+
+/// Parses the age field of a row according to `policy`. Empty and non-numeric ages are errors under either policy.
+fn parse_age(input: &str, policy: AgePolicy) -> Result<u8, ASPRError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(ASPRError::Parse(FIPSParserError::Empty));
+    }
+    if let Some(found) = input.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(ASPRError::Parse(FIPSParserError::InvalidDigit { found }));
+    }
+    let (_, age) = parse_integer(input).map_err(|(_, error)| ASPRError::Parse(error))?;
+
+    match (u8::try_from(age), policy) {
+        (Ok(age), _) => Ok(age),
+        (Err(_), AgePolicy::Clamp) => Ok(u8::MAX),
+        (Err(_), AgePolicy::Reject) => Err(ASPRError::Parse(
+            FIPSParserError::ValueExceedsCapacity {
+                value: age,
+                capacity: u64::from(u8::MAX),
+            },
+        )),
+    }
+}
+
+/// Returns the number of records in `file_path`, a path relative to the ASPR data path. The header row, if present, is
+/// not counted. This is the preferred way to size a file: rather than parsing each record, it scans the file for line
+/// breaks in buffered chunks.
+pub fn count_records(file_path: PathBuf) -> Result<usize, ASPRError> {
+    let path = get_aspr_data_path();
+
+    if is_zip_archive_path(&path) {
+        // The names of the files in a zip archive are strings.
+        let name = file_path.to_str().ok_or_else(|| ASPRError::FileIo {
+            path: file_path.clone(),
+            error: std::io::Error::new(ErrorKind::InvalidInput, "path is not valid UTF-8"),
+        })?;
+        let file = File::open(path).map_err(ASPRError::Io)?;
+        let mut archive = ZipArchive::new(BufReader::new(file)).map_err(ASPRError::ZipError)?;
+        let zipped_file = archive.by_name(name).map_err(ASPRError::ZipError)?;
+        count_lines_after_header(BufReader::new(zipped_file))
+    } else {
+        let file = File::open(path.join(file_path)).map_err(ASPRError::Io)?;
+        count_lines_after_header(BufReader::new(file))
+    }
+}
+
+/// Counts the lines of `reader`, not counting the first line if it is a header row. A final line without a line break is
+/// counted.
+fn count_lines_after_header(mut reader: impl BufRead) -> Result<usize, ASPRError> {
+    let mut first_line = String::new();
+    if reader.read_line(&mut first_line).map_err(ASPRError::Io)? == 0 {
+        return Ok(0);
+    }
+    let header = normalize_header(&first_line);
+    let mut count = usize::from(!is_header_row(header, detect_delimiter(header)));
+
+    let mut ends_with_line_break = true;
+    loop {
+        let buffer = reader.fill_buf().map_err(ASPRError::Io)?;
+        let Some(&last_byte) = buffer.last() else {
+            break;
+        };
+        count += memchr_iter(b'\n', buffer).count();
+        ends_with_line_break = last_byte == b'\n';
+        let length = buffer.len();
+        reader.consume(length);
+    }
+
+    Ok(count + usize::from(!ends_with_line_break))
+}
+
+/// Returns the number of records in each of `files`, keyed by path, without parsing the records. The header row, if
+/// present, is not counted. Paths are relative to the ASPR data path, as returned by `iter_csv_files`.
+pub fn count_records_per_file(
+    files: impl IntoIterator<Item = PathBuf>,
+) -> Result<HashMap<PathBuf, usize>, ASPRError> {
+    let mut counts = HashMap::new();
+
+    for file_path in files {
+        let count = count_records(file_path.clone())?;
+        counts.insert(file_path, count);
+    }
+
+    Ok(counts)
+}
+
+/// Returns the total number of records in every CSV file under `root`, a subdirectory of the ASPR data path, searched
+/// recursively. Pass an empty path to count the whole dataset. Header rows are not counted. Symlinked directories are not
+/// searched.
+pub fn total_record_count(root: impl AsRef<Path>) -> Result<u64, ASPRError> {
+    let is_csv = |path: &Path| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+    };
+    let path = get_aspr_data_path();
+
+    let files: Vec<PathBuf> = if is_zip_archive_path(&path) {
+        // Matching on the path prefix already finds the files of nested subdirectories.
+        iter_csv_files(root)?.filter(|file| is_csv(file)).collect()
+    } else {
+        let mut files = vec![];
+        let mut directories = vec![path.join(root)];
+        while let Some(directory) = directories.pop() {
+            for entry in directory.read_dir().map_err(ASPRError::Io)? {
+                let entry = entry.map_err(ASPRError::Io)?;
+                let entry_path = entry.path();
+                // Unlike `Path::is_dir`, `DirEntry::file_type` does not follow symlinks, so symlinked directories are
+                // skipped rather than walked, and a symlink to an ancestor directory cannot make the walk loop forever.
+                if entry.file_type().map_err(ASPRError::Io)?.is_dir() {
+                    directories.push(entry_path);
+                } else if is_csv(&entry_path) {
+                    // `count_records` takes paths relative to the ASPR data path, as in the zip branch.
+                    let relative_path = entry_path
+                        .strip_prefix(&path)
+                        .expect("walked paths are under the ASPR data path");
+                    files.push(relative_path.to_path_buf());
+                }
+            }
+        }
+        files
+    };
+
+    Ok(count_records_per_file(files)?
+        .into_values()
+        .map(|count| count as u64)
+        .sum())
+}
+
+/// Returns all the data files in each of the given subdirectories of the ASPR data path, concatenated in the order of
+/// `subdirectories`. If `skip_missing` is `true`, subdirectories that do not exist are skipped; otherwise a missing
+/// subdirectory is an error. (Within a zip archive, a missing subdirectory simply contains no files.)
+pub fn iter_csv_files_many<P: AsRef<Path>>(
+    subdirectories: &[P],
+    skip_missing: bool,
+) -> Result<Vec<PathBuf>, ASPRError> {
+    let mut files = vec![];
+
+    for subdirectory in subdirectories {
+        match iter_csv_files(subdirectory) {
+            Ok(subdirectory_files) => files.extend(subdirectory_files),
+            Err(ASPRError::Io(e)) if skip_missing && e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(files)
+}
+
 /// Iterator over ASPR records in a particular ASPR data file.
 pub struct ASPRRecordIterator {
     line_iter: LineIterator,
+    /// The path of the file, relative to the ASPR data path, for error reporting.
+    file_path: PathBuf,
+    /// The number of lines of the file read so far, including the header row.
+    line_number: usize,
+    /// The field delimiter, detected from the header row.
+    delimiter: char,
+    /// The first line of a file without a header row, which is a data row that must be yielded before reading on.
+    pending_line: Option<String>,
+    age_policy: AgePolicy,
 }
 
 impl ASPRRecordIterator {
@@ -266,18 +563,52 @@ impl ASPRRecordIterator {
     }
 
     /// Returns an iterator over the records in `file_path`. This function is intended to be used with the
-    /// `iter_csv_files` function.
+    /// `iter_csv_files` function. The field delimiter, either a comma or a tab, is detected from the header row.
+    ///
+    /// Some files, such as raw data dumps, have no header row. The first line is taken to be a data row rather than a
+    /// header if its first field is a number, so that it is not silently dropped.
     pub fn from_path(file_path: PathBuf) -> Result<Self, ASPRError> {
         // let file          = File::open(path.clone()).map_err(ASPRError::Io)?;
         let mut line_iter = LineIterator::from_path(file_path.clone())?;
 
-        // Skip the header row
-        if line_iter.next().is_none() {
+        // Skip the header row, if there is one
+        let Some(first_line) = line_iter.next() else {
             // If there is no header row, something is wrong, so return an error.
             return Err(ASPRError::EmptyFile(file_path));
+        };
+        let first_line = first_line?;
+
+        // Some files carry a UTF-8 BOM or a trailing `\r` on the header line.
+        let header = normalize_header(&first_line);
+        let delimiter = detect_delimiter(header);
+        let has_header = is_header_row(header, delimiter);
+        let found = header.split(delimiter).count();
+        if has_header && found < ASPR_COLUMN_COUNT {
+            return Err(ASPRError::MalformedHeader {
+                path: file_path,
+                expected: ASPR_COLUMN_COUNT,
+                found,
+            });
         }
 
-        Ok(Self { line_iter })
+        // A data row is kept as read, but for the BOM, so that it parses like every other row. In particular, trimming
+        // would drop the delimiter before an empty last field of a tab-separated row.
+        let pending_line = (!has_header).then(|| {
+            first_line
+                .strip_prefix('\u{feff}')
+                .unwrap_or(&first_line)
+                .to_string()
+        });
+
+        Ok(Self {
+            line_iter,
+            file_path,
+            // The pending first line of a file without a header row is counted when it is read.
+            line_number: usize::from(has_header),
+            delimiter,
+            pending_line,
+            age_policy: AgePolicy::default(),
+        })
     }
 
     /// Returns an iterator over all the rows of all the files in the iterator. This function is intended to be used with
@@ -296,47 +627,1214 @@ impl ASPRRecordIterator {
             // Each successful iterator yields records; flatten them all.
             .flatten()
     }
+
+    /// Like `from_file_iterator`, but yields `Result`s so that no file is skipped silently. A file that cannot be
+    /// opened, is empty, or has a malformed header yields a single error, and the records of each file that can be
+    /// opened are yielded as by `checked`. I/O errors are reported as `ASPRError::FileIo` with the path of the file.
+    /// Iteration continues with the following line or file after an error.
+    pub fn from_file_iterator_checked(
+        files: impl Iterator<Item = PathBuf>,
+    ) -> impl Iterator<Item = Result<ASPRPersonRecord, ASPRError>> {
+        files.flat_map(|path| {
+            let (records, error) = match ASPRRecordIterator::from_path(path.clone()) {
+                Ok(records) => (Some(records.checked()), None),
+                Err(error) => (None, Some(error)),
+            };
+            error
+                .map(Err)
+                .into_iter()
+                .chain(records.into_iter().flatten())
+                .map(move |result| result.map_err(|error| with_file_path(error, &path)))
+        })
+    }
+
+    /// Returns an iterator over all the rows of the data files listed in the manifest file `manifest_path`, in the
+    /// order listed. The manifest lists one path per line, relative to the ASPR data path; blank lines are ignored.
+    /// The existence of every listed file is checked before this function returns, so that a missing file is reported,
+    /// as an `ASPRError::FileIo` naming the file, before any records are read. The files themselves are opened one at
+    /// a time as iteration reaches them, so that a long manifest does not hold many files open at once; as with
+    /// `from_file_iterator`, a file that exists but cannot be opened, e.g. one that is empty, is skipped.
+    pub fn from_manifest(
+        manifest_path: impl AsRef<Path>,
+    ) -> Result<impl Iterator<Item = ASPRPersonRecord>, ASPRError> {
+        let manifest_path = manifest_path.as_ref();
+        let manifest =
+            std::fs::read_to_string(manifest_path).map_err(|error| ASPRError::FileIo {
+                path: manifest_path.to_path_buf(),
+                error,
+            })?;
+
+        let files: Vec<PathBuf> = manifest
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        check_files_exist(&files)?;
+        Ok(Self::from_file_iterator(files.into_iter()))
+    }
+
+    /// Returns the first `n` records in `file_path`, or all of them if there are fewer than `n`, e.g. for a preview. The
+    /// file is closed before this function returns.
+    pub fn head(file_path: PathBuf, n: usize) -> Result<Vec<ASPRPersonRecord>, ASPRError> {
+        Ok(Self::from_path(file_path)?.take(n).collect())
+    }
+
+    /// Sets how ages that do not fit in a `u8` are treated. By default they are rejected: the unchecked iterator skips
+    /// their rows, and the `checked` iterator reports them as `ASPRError::Field` errors.
+    #[must_use]
+    pub fn with_age_policy(mut self, age_policy: AgePolicy) -> Self {
+        self.age_policy = age_policy;
+        self
+    }
+
+    /// Converts this iterator into one that also yields the original text of each id field, so that ids that fail to
+    /// parse are not lost.
+    #[must_use]
+    pub fn with_raw_fields(self) -> ASPRRawRecordIterator {
+        ASPRRawRecordIterator(self)
+    }
+
+    /// Converts this iterator into one that keeps any text trailing the id in each id field. The id is taken to be the
+    /// leading 15 (home), 14 (school), or 16 (workplace) characters of its field, and the remainder is returned
+    /// alongside the record rather than causing the id to fail to parse.
+    #[must_use]
+    pub fn with_annotations(self) -> ASPRAnnotatedRecordIterator {
+        ASPRAnnotatedRecordIterator(self)
+    }
+
+    /// Converts this iterator into one that yields `Result`s, so that a line that cannot be read, for example because
+    /// it is not valid UTF-8, is reported as an `ASPRError::Io` rather than ending iteration as if the end of the file
+    /// had been reached. A malformed age or nonempty id is reported as an `ASPRError::Field` giving the file, the line
+    /// number, the column and name of the field, and the byte offset within the line at which parsing failed, and a row
+    /// with too few fields as an `ASPRError::MalformedRow`. Iteration continues with the following line after an error.
+    #[must_use]
+    pub fn checked(self) -> ASPRCheckedRecordIterator {
+        ASPRCheckedRecordIterator(self)
+    }
+
+    /// Reads the next line and splits it into the age and the three trimmed id fields, which are passed to `f` along
+    /// with the line and its location. Returns `None` at the end of the file and `Some(Err(_))` if the line could not be
+    /// read, has too few fields, or its age could not be parsed.
+    fn next_fields<T>(
+        &mut self,
+        f: impl FnOnce(&LineContext, u8, &str, &str, &str) -> T,
+    ) -> Option<Result<T, ASPRError>> {
+        let line = match self.next_line()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e)),
+        };
+        let context = LineContext {
+            file_path: &self.file_path,
+            line_number: self.line_number,
+            line: &line,
+        };
+        let mut part_iter = line.split(self.delimiter).map(str::trim);
+        let (Some(age_str), Some(home_id_str), Some(school_id_str), Some(work_id_str)) = (
+            part_iter.next(),
+            part_iter.next(),
+            part_iter.next(),
+            part_iter.next(),
+        ) else {
+            return Some(Err(ASPRError::MalformedRow {
+                path: self.file_path.clone(),
+                line: self.line_number,
+                expected: ASPR_COLUMN_COUNT,
+                found: line.split(self.delimiter).count(),
+            }));
+        };
+
+        let age = match parse_age(age_str, self.age_policy) {
+            Ok(age) => age,
+            Err(ASPRError::Parse(error)) => {
+                return Some(Err(context.field_error(AGE_COLUMN, age_str, age_str, error)))
+            }
+            Err(e) => return Some(Err(e)),
+        };
+
+        Some(Ok(f(
+            &context,
+            age,
+            home_id_str,
+            school_id_str,
+            work_id_str,
+        )))
+    }
+
+    /// Like `next_fields`, but skips rows that fail to parse rather than returning an error for them, for the iterators
+    /// that do not report errors. Returns `None` at the end of the file or if a line could not be read, as a read error
+    /// may persist.
+    fn next_valid_fields<T>(
+        &mut self,
+        f: impl Fn(&LineContext, u8, &str, &str, &str) -> T,
+    ) -> Option<T> {
+        loop {
+            match self.next_fields(&f)? {
+                Ok(item) => return Some(item),
+                Err(ASPRError::Io(_)) => return None,
+                Err(_) => {}
+            }
+        }
+    }
+
+    /// Reads the next data line, counting it in `line_number`. Returns `None` at the end of the file.
+    fn next_line(&mut self) -> Option<Result<String, ASPRError>> {
+        let line = match self.pending_line.take() {
+            Some(line) => Ok(line),
+            None => self.line_iter.next()?,
+        };
+        self.line_number += 1;
+        Some(line)
+    }
+}
+
+/// Returns an `ASPRError::FileIo` naming the first of `files`, paths relative to the ASPR data path, that does not exist.
+fn check_files_exist(files: &[PathBuf]) -> Result<(), ASPRError> {
+    let path = get_aspr_data_path();
+
+    if is_zip_archive_path(&path) {
+        let file = File::open(path).map_err(ASPRError::Io)?;
+        let archive = ZipArchive::new(BufReader::new(file)).map_err(ASPRError::ZipError)?;
+        let file_names: HashSet<&str> = archive.file_names().collect();
+        for file_path in files {
+            if !file_path
+                .to_str()
+                .is_some_and(|name| file_names.contains(name))
+            {
+                return Err(ASPRError::FileIo {
+                    path: file_path.clone(),
+                    error: ErrorKind::NotFound.into(),
+                });
+            }
+        }
+        Ok(())
+    } else {
+        for file_path in files {
+            std::fs::metadata(path.join(file_path)).map_err(|error| ASPRError::FileIo {
+                path: file_path.clone(),
+                error,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Attaches `path` to `error` if it is an I/O error without a path.
+fn with_file_path(error: ASPRError, path: &Path) -> ASPRError {
+    match error {
+        ASPRError::Io(error) => ASPRError::FileIo {
+            path: path.to_path_buf(),
+            error,
+        },
+        error => error,
+    }
+}
+
+/// Counts of the problems found in an ASPR data file by `validate_file`.
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub struct FileHealthReport {
+    /// The number of data rows, not counting the header row.
+    pub total_rows: usize,
+    /// The number of rows that could not be read, e.g. because they are not valid UTF-8.
+    pub unreadable_rows: usize,
+    /// The number of rows with fewer fields than an ASPR record requires.
+    pub short_rows: usize,
+    /// The number of fields that failed to parse, keyed by column name, e.g. `"workplaceId"`. An empty id is an absent
+    /// id rather than a failure, and an age too large for a `u8` is counted in `age_range_violations` instead.
+    pub parse_failures: BTreeMap<&'static str, usize>,
+    /// The number of numeric ages too large for a `u8`.
+    pub age_range_violations: usize,
+    /// The number of ids that parsed but whose state code is not one of the `USState`s, e.g. 72 for Puerto Rico.
+    pub unknown_state_codes: usize,
+}
+
+impl FileHealthReport {
+    /// Returns `true` if no problems were found.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.unreadable_rows == 0
+            && self.short_rows == 0
+            && self.parse_failures.is_empty()
+            && self.age_range_violations == 0
+            && self.unknown_state_codes == 0
+    }
+}
+
+/// Scans every row of `file_path`, a path relative to the ASPR data path, and counts the problems found, for acceptance
+/// testing of a data file. Unlike the record iterators, which skip a bad row or report only its first error, every field
+/// of every row is checked. Returns an error only if the file cannot be opened or its header row is malformed.
+pub fn validate_file(file_path: PathBuf) -> Result<FileHealthReport, ASPRError> {
+    let mut records = ASPRRecordIterator::from_path(file_path)?;
+    let mut report = FileHealthReport::default();
+    let id_parsers: [(usize, IdParser); 3] = [
+        (HOME_ID_COLUMN, parse_fips_home_id),
+        (SCHOOL_ID_COLUMN, parse_fips_school_id),
+        (WORK_ID_COLUMN, parse_fips_workplace_id),
+    ];
+
+    while let Some(line) = records.next_line() {
+        report.total_rows += 1;
+        let Ok(line) = line else {
+            report.unreadable_rows += 1;
+            continue;
+        };
+        let fields: Vec<&str> = line.split(records.delimiter).map(str::trim).collect();
+        if fields.len() < ASPR_COLUMN_COUNT {
+            report.short_rows += 1;
+            continue;
+        }
+
+        let mut parse_failure = |column: usize| {
+            *report
+                .parse_failures
+                .entry(ASPR_COLUMN_NAMES[column])
+                .or_default() += 1;
+        };
+        match parse_age(fields[AGE_COLUMN], AgePolicy::Reject) {
+            Ok(_) => {}
+            Err(ASPRError::Parse(
+                FIPSParserError::ValueExceedsCapacity { .. } | FIPSParserError::Overflow { .. },
+            )) => report.age_range_violations += 1,
+            Err(_) => parse_failure(AGE_COLUMN),
+        }
+        for (column, parser) in id_parsers {
+            if fields[column].is_empty() {
+                continue;
+            }
+            match parser(fields[column]) {
+                Ok((_, id)) if id.state().is_err() => report.unknown_state_codes += 1,
+                Ok(_) => {}
+                Err(_) => parse_failure(column),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// The names of the columns of an ASPR data file, in order.
+const ASPR_COLUMN_NAMES: [&str; ASPR_COLUMN_COUNT] = ["age", "homeId", "schoolId", "workplaceId"];
+const AGE_COLUMN: usize = 0;
+const HOME_ID_COLUMN: usize = 1;
+const SCHOOL_ID_COLUMN: usize = 2;
+const WORK_ID_COLUMN: usize = 3;
+
+/// A parser of one of the id columns, e.g. `parse_fips_home_id`.
+type IdParser = fn(&str) -> FIPSParseResult<FIPSCode>;
+
+/// A line of a data file together with its location, for reporting parse errors.
+struct LineContext<'a> {
+    file_path: &'a Path,
+    line_number: usize,
+    line: &'a str,
+}
+
+impl LineContext<'_> {
+    /// Returns an `ASPRError::Field` for `error`, which occurred parsing `field_str`, the subslice of the line in column
+    /// `column`. `error_input` is the suffix of `field_str` the failing parser was given, e.g. the id following the
+    /// `"xprvx"` marker of a private school id. An invalid digit is located at the first character of `error_input` that
+    /// is not an ASCII digit; other errors at the start of `error_input`.
+    fn field_error(
+        &self,
+        column: usize,
+        field_str: &str,
+        error_input: &str,
+        error: FIPSParserError,
+    ) -> ASPRError {
+        let field_offset = field_str.as_ptr() as usize - self.line.as_ptr() as usize;
+        let error_offset = field_offset + field_str.len().saturating_sub(error_input.len());
+        let offset = match error {
+            FIPSParserError::InvalidDigit { .. } => {
+                error_offset + error_input.find(|c: char| !c.is_ascii_digit()).unwrap_or(0)
+            }
+            _ => error_offset,
+        };
+        ASPRError::Field {
+            path: self.file_path.to_path_buf(),
+            line: self.line_number,
+            column,
+            field: ASPR_COLUMN_NAMES[column],
+            offset,
+            error,
+        }
+    }
+
+    /// Parses the id in `id_str`, the field of the line in column `column`, with `parser`. An empty field is an absent
+    /// id.
+    fn parse_id(
+        &self,
+        column: usize,
+        id_str: &str,
+        parser: IdParser,
+    ) -> Result<Option<FIPSCode>, ASPRError> {
+        if id_str.is_empty() {
+            return Ok(None);
+        }
+        parser(id_str)
+            .map(|(_, id)| Some(id))
+            .map_err(|(error_input, error)| self.field_error(column, id_str, error_input, error))
+    }
+}
+
+/// Parses the fields of a row into an `ASPRPersonRecord`, storing `None` for ids that fail to parse.
+fn parse_record(
+    age: u8,
+    home_id_str: &str,
+    school_id_str: &str,
+    work_id_str: &str,
+) -> ASPRPersonRecord {
+    let home_id = parse_fips_home_id(home_id_str).ok().map(|(_, id)| id);
+    let school_id = parse_fips_school_id(school_id_str).ok().map(|(_, id)| id);
+    let work_id = parse_fips_workplace_id(work_id_str).ok().map(|(_, id)| id);
+
+    ASPRPersonRecord {
+        age,
+        home_id,
+        school_id,
+        work_id,
+    }
 }
 
 impl Iterator for ASPRRecordIterator {
     type Item = ASPRPersonRecord;
 
-    /// Returns the next record in the ASPR data file. This function returns `None` on malformed data. We assume
-    /// that the prepared data is well-formed.
+    /// Returns the next record in the ASPR data file. A row whose age cannot be parsed is skipped, and a line that cannot
+    /// be read ends iteration. We assume that the prepared data is well-formed; use `checked` to detect these errors.
     fn next(&mut self) -> Option<Self::Item> {
-        let line = (self.line_iter.next()?).ok()?;
-        let mut part_iter = line.split(',');
+        self.next_valid_fields(|_, age, home_id_str, school_id_str, work_id_str| {
+            parse_record(age, home_id_str, school_id_str, work_id_str)
+        })
+    }
+}
 
-        let age = part_iter.next()?.parse::<u8>().unwrap();
+/// An iterator over the records of an ASPR data file that reports read and parse errors. Created with
+/// `ASPRRecordIterator::checked`.
+pub struct ASPRCheckedRecordIterator(ASPRRecordIterator);
 
-        let home_id_str = part_iter.next()?.trim();
-        let home_id = parse_fips_home_id(home_id_str).ok().map(|(_, id)| id);
+impl Iterator for ASPRCheckedRecordIterator {
+    type Item = Result<ASPRPersonRecord, ASPRError>;
 
-        let school_id_str = part_iter.next()?.trim();
-        let school_id = parse_fips_school_id(school_id_str).ok().map(|(_, id)| id);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next_fields(|context, age, home_id_str, school_id_str, work_id_str| {
+                Ok(ASPRPersonRecord {
+                    age,
+                    home_id: context.parse_id(HOME_ID_COLUMN, home_id_str, parse_fips_home_id)?,
+                    school_id: context.parse_id(
+                        SCHOOL_ID_COLUMN,
+                        school_id_str,
+                        parse_fips_school_id,
+                    )?,
+                    work_id: context.parse_id(
+                        WORK_ID_COLUMN,
+                        work_id_str,
+                        parse_fips_workplace_id,
+                    )?,
+                })
+            })
+            .map(|result| result.and_then(std::convert::identity))
+    }
+}
 
-        let work_id_str = part_iter.next()?.trim();
-        let work_id = parse_fips_workplace_id(work_id_str).ok().map(|(_, id)| id);
+/// An iterator over the records of an ASPR data file that retains the original text of each id field. Created with
+/// `ASPRRecordIterator::with_raw_fields`.
+pub struct ASPRRawRecordIterator(ASPRRecordIterator);
 
-        Some(ASPRPersonRecord {
-            age,
-            home_id,
-            school_id,
-            work_id,
-        })
+impl Iterator for ASPRRawRecordIterator {
+    type Item = ASPRPersonRecordRaw;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next_valid_fields(|_, age, home_id_str, school_id_str, work_id_str| {
+                ASPRPersonRecordRaw {
+                    record: parse_record(age, home_id_str, school_id_str, work_id_str),
+                    home_id: home_id_str.to_string(),
+                    school_id: school_id_str.to_string(),
+                    work_id: work_id_str.to_string(),
+                }
+            })
+    }
+}
+
+/// Splits `field` after its first `id_len` characters into the id and the trailing text. A field no longer than `id_len`
+/// is all id.
+fn split_trailing(field: &str, id_len: usize) -> (&str, &str) {
+    if field.is_char_boundary(id_len) && field.len() > id_len {
+        field.split_at(id_len)
+    } else {
+        (field, "")
+    }
+}
+
+/// An iterator over the records of an ASPR data file that retains any text trailing each id. Created with
+/// `ASPRRecordIterator::with_annotations`.
+pub struct ASPRAnnotatedRecordIterator(ASPRRecordIterator);
+
+impl Iterator for ASPRAnnotatedRecordIterator {
+    type Item = ASPRPersonRecordAnnotated;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next_valid_fields(|_, age, home_id_str, school_id_str, work_id_str| {
+                let (home_id_str, home_rest) = split_trailing(home_id_str, 15);
+                let (school_id_str, school_rest) = split_trailing(school_id_str, 14);
+                let (work_id_str, work_rest) = split_trailing(work_id_str, 16);
+                ASPRPersonRecordAnnotated {
+                    record: parse_record(age, home_id_str, school_id_str, work_id_str),
+                    home_rest: home_rest.to_string(),
+                    school_rest: school_rest.to_string(),
+                    work_rest: work_rest.to_string(),
+                }
+            })
     }
 }
 
-#[cfg(all(any(feature = "aspr_tests", feature = "aspr_dataset_tests", feature = "aspr_zip_tests"), test))]
+/// Returns an iterator over the records in `file_path` whose home id lies in the census tract containing `tract`.
+///
+/// The file is assumed to be sorted by home id (GEOID), which in turn sorts records hierarchically by state, county,
+/// and tract. Under this assumption the records of a tract are contiguous, so the iterator skips records until it
+/// reaches the tract and stops reading as soon as it passes the tract, rather than reading the whole file. If the file
+/// is not sorted, records of the tract appearing after the first record of a different tract are missed. Records without
+/// a home id are skipped and do not end the run of the tract.
+pub fn records_in_tract(
+    file_path: PathBuf,
+    tract: FIPSCode,
+) -> Result<impl Iterator<Item = ASPRPersonRecord>, ASPRError> {
+    let tract = Some(tract.parent_tract());
+    let home_tract =
+        |record: &ASPRPersonRecord| record.home_id.map(|home_id| home_id.parent_tract());
+
+    Ok(ASPRRecordIterator::from_path(file_path)?
+        .skip_while(move |record| home_tract(record) < tract)
+        // A record without a home id does not end the run of the tract, but it is not in the tract either.
+        .take_while(move |record| record.home_id.is_none() || home_tract(record) == tract)
+        .filter(|record| record.home_id.is_some()))
+}
+
+#[cfg(test)]
 mod tests {
-    //! These tests assume the existence of data in the default ASPR data path AND the existence of the zip archive
-    //! in the default ASPR data path.
+    //! The feature-gated tests assume the existence of data in the default ASPR data path AND the existence of the zip
+    //! archive in the default ASPR data path. The remaining tests use the small fixtures in `test_data`.
     use super::*;
 
     // Enforce serial execution of tests. Since the "zip" tests change the ASPR data path, we also need to set the
     // ASPR data path to the default value before running the tests.
     static TEST_MUTEX: Lazy<std::sync::Mutex<()>> = Lazy::new(|| std::sync::Mutex::new(()));
 
+    /// Path to the fixture files checked into this crate.
+    fn test_data_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data")
+    }
+
+    #[test]
+    fn test_bom_prefixed_header() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        let records: Vec<ASPRPersonRecord> =
+            ASPRRecordIterator::from_path(PathBuf::from("bom_header.csv"))
+                .unwrap()
+                .collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].age, 34);
+        assert!(records[0].school_id.is_none());
+        assert!(records[1].school_id.is_some());
+    }
+
+    #[test]
+    fn test_headerless_file() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        // The first line is a data row and must not be dropped.
+        let records: Vec<ASPRPersonRecord> =
+            ASPRRecordIterator::from_path(PathBuf::from("no_header.csv"))
+                .unwrap()
+                .collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].age, 34);
+        assert!(records[0].home_id.is_some());
+        assert!(records[0].work_id.is_some());
+        assert_eq!(records[2].age, 12);
+
+        // A file with a header yields the same records.
+        let with_header: Vec<ASPRPersonRecord> =
+            ASPRRecordIterator::from_path(PathBuf::from("bom_header.csv"))
+                .unwrap()
+                .collect();
+        assert_eq!(records, with_header);
+    }
+
+    #[test]
+    fn test_head() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+        let path = PathBuf::from("sorted_by_home.csv");
+
+        let records = ASPRRecordIterator::head(path.clone(), 5).unwrap();
+        let ages: Vec<u8> = records.iter().map(|record| record.age).collect();
+        assert_eq!(ages, vec![41, 39, 7, 66, 25]);
+
+        assert_eq!(ASPRRecordIterator::head(path.clone(), 100).unwrap().len(), 9);
+        assert!(ASPRRecordIterator::head(path, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_short_header() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        let result = ASPRRecordIterator::from_path(PathBuf::from("short_header.csv"));
+        assert!(matches!(
+            result,
+            Err(ASPRError::MalformedHeader {
+                expected: ASPR_COLUMN_COUNT,
+                found: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_records_in_tract() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        let path = PathBuf::from("sorted_by_home.csv");
+        let tract = FIPSCode::with_tract(USState::DC.into(), 1, 10910).unwrap();
+        let records: Vec<ASPRPersonRecord> =
+            records_in_tract(path.clone(), tract).unwrap().collect();
+        assert_eq!(records.len(), 3);
+        assert!(records
+            .iter()
+            .all(|record| record.home_id.unwrap().parent_tract() == tract));
+
+        // A code within the tract selects the same records as the tract itself.
+        let home = records[0].home_id.unwrap();
+        assert_eq!(records_in_tract(path.clone(), home).unwrap().count(), 3);
+
+        // A tract that is absent from the file yields nothing.
+        let tract = FIPSCode::with_tract(USState::DC.into(), 1, 10905).unwrap();
+        assert_eq!(records_in_tract(path, tract).unwrap().count(), 0);
+
+        // A record without a home id within the run of the tract is skipped rather than ending the run.
+        let tract = FIPSCode::with_tract(USState::DC.into(), 1, 10910).unwrap();
+        let ages: Vec<u8> = records_in_tract(PathBuf::from("missing_home_in_tract.csv"), tract)
+            .unwrap()
+            .map(|record| record.age)
+            .collect();
+        assert_eq!(ages, vec![25, 3, 52]);
+    }
+
+    #[test]
+    fn test_tab_separated() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        let records: Vec<ASPRPersonRecord> =
+            ASPRRecordIterator::from_path(PathBuf::from("tab_separated.tsv"))
+                .unwrap()
+                .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].age, 34);
+        assert!(records[0].home_id.is_some());
+        assert!(records[0].work_id.is_some());
+        assert!(records[1].school_id.is_some());
+    }
+
+    #[test]
+    fn test_headerless_tab_separated_file() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+        let path = PathBuf::from("no_header.tsv");
+
+        // The first line, a data row, ends with an empty workplace id, which must survive like that of any other row.
+        let results: Vec<Result<ASPRPersonRecord, ASPRError>> =
+            ASPRRecordIterator::from_path(path.clone())
+                .unwrap()
+                .checked()
+                .collect();
+        assert_eq!(results.len(), 2);
+        let first = results[0].as_ref().unwrap();
+        assert_eq!(first.age, 8);
+        assert!(first.school_id.is_some());
+        assert!(first.work_id.is_none());
+        assert!(results[1].as_ref().unwrap().work_id.is_none());
+
+        let report = validate_file(path).unwrap();
+        assert_eq!(report.total_rows, 2);
+        assert!(report.is_healthy());
+    }
+
+    #[test]
+    fn test_checked_field_error_offset() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        let results: Vec<Result<ASPRPersonRecord, ASPRError>> =
+            ASPRRecordIterator::from_path(PathBuf::from("malformed_work_id.csv"))
+                .unwrap()
+                .checked()
+                .collect();
+        assert_eq!(results.len(), 2);
+
+        // The line is `34,110010109000024,,11001006202O1546`; the offset points at the letter `O`.
+        let line = "34,110010109000024,,11001006202O1546";
+        match &results[0] {
+            Err(ASPRError::Field {
+                field,
+                offset,
+                error,
+                ..
+            }) => {
+                assert_eq!(*field, "workplaceId");
+                assert_eq!(line.as_bytes()[*offset], b'O');
+                assert_eq!(*error, FIPSParserError::InvalidDigit { found: 'O' });
+            }
+            other => panic!("expected a field error, found {:?}", other),
+        }
+        assert!(results[1].as_ref().unwrap().work_id.is_some());
+    }
+
+    #[test]
+    fn test_from_file_iterator_checked() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        let paths = ["no_header.csv", "missing.csv", "invalid_utf8.csv"].map(PathBuf::from);
+        let results: Vec<_> =
+            ASPRRecordIterator::from_file_iterator_checked(paths.into_iter()).collect();
+
+        assert_eq!(results.len(), 7);
+        let ages: Vec<u8> = results.iter().flatten().map(|record| record.age).collect();
+        assert_eq!(ages, vec![34, 8, 12, 34, 12]);
+        assert!(matches!(
+            &results[3],
+            Err(ASPRError::FileIo { path, error })
+                if path == Path::new("missing.csv") && error.kind() == std::io::ErrorKind::NotFound
+        ));
+        // A line that is not valid UTF-8 is reported with its file rather than ending iteration.
+        assert!(matches!(
+            &results[5],
+            Err(ASPRError::FileIo { path, .. }) if path == Path::new("invalid_utf8.csv")
+        ));
+
+        // The unchecked iterator drops the missing file.
+        let paths = ["no_header.csv", "missing.csv"].map(PathBuf::from);
+        assert_eq!(
+            ASPRRecordIterator::from_file_iterator(paths.into_iter()).count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_from_manifest() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        let records =
+            ASPRRecordIterator::from_manifest(test_data_path().join("manifest.txt")).unwrap();
+        let ages: Vec<u8> = records.map(|record| record.age).collect();
+        assert_eq!(ages, vec![34, 8, 12, 34, 8]);
+
+        let manifest_path =
+            std::env::temp_dir().join(format!("ixa_aspr_manifest_{}.txt", std::process::id()));
+        std::fs::write(&manifest_path, "no_header.csv\n\nmissing.csv\n").unwrap();
+        let result = ASPRRecordIterator::from_manifest(&manifest_path);
+        std::fs::remove_file(&manifest_path).unwrap();
+        match result {
+            Err(error @ ASPRError::FileIo { .. }) => {
+                assert!(error.to_string().contains("missing.csv"), "{error}");
+            }
+            Err(error) => panic!("expected a missing file error, got {error}"),
+            Ok(_) => panic!("expected a missing file error"),
+        }
+
+        assert!(matches!(
+            ASPRRecordIterator::from_manifest(test_data_path().join("no_such_manifest.txt")),
+            Err(ASPRError::FileIo { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_file() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        let report = validate_file(PathBuf::from("health_check.csv")).unwrap();
+        assert_eq!(report.total_rows, 9);
+        assert_eq!(report.unreadable_rows, 1);
+        assert_eq!(report.short_rows, 1);
+        assert_eq!(
+            report.parse_failures,
+            BTreeMap::from([
+                ("age", 1),
+                ("homeId", 1),
+                ("schoolId", 1),
+                ("workplaceId", 1)
+            ])
+        );
+        assert_eq!(report.age_range_violations, 1);
+        assert_eq!(report.unknown_state_codes, 1);
+        assert!(!report.is_healthy());
+
+        let report = validate_file(PathBuf::from("sorted_by_home.csv")).unwrap();
+        assert!(report.total_rows > 0);
+        assert!(report.is_healthy());
+    }
+
+    #[test]
+    fn test_checked_field_error_context() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+        let path = PathBuf::from("malformed_work_id.csv");
+
+        let error = ASPRRecordIterator::from_path(path.clone())
+            .unwrap()
+            .checked()
+            .find_map(Result::err)
+            .unwrap();
+        match error {
+            ASPRError::Field {
+                path: error_path,
+                line,
+                column,
+                field,
+                offset,
+                error,
+            } => {
+                assert_eq!(error_path, path);
+                // The header row is line 1.
+                assert_eq!(line, 2);
+                assert_eq!(column, 3);
+                assert_eq!(field, "workplaceId");
+                assert_eq!(offset, 31);
+                assert_eq!(error, FIPSParserError::InvalidDigit { found: 'O' });
+            }
+            other => panic!("expected a field error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_checked_private_school_error_offset() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        // The invalid digit follows the `xprvx` marker, whose letters must not be mistaken for it.
+        let line = "12,240310001000010,24031xprvx01a0,";
+        let error = ASPRRecordIterator::from_path(PathBuf::from("malformed_private_school_id.csv"))
+            .unwrap()
+            .checked()
+            .find_map(Result::err)
+            .unwrap();
+        match error {
+            ASPRError::Field {
+                line: 2,
+                field: "schoolId",
+                offset,
+                error,
+                ..
+            } => {
+                assert_eq!(offset, 31);
+                assert_eq!(line.as_bytes()[offset], b'a');
+                assert_eq!(error, FIPSParserError::InvalidDigit { found: 'a' });
+            }
+            other => panic!("expected a field error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_raw_fields_preserved() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+
+        let records: Vec<ASPRPersonRecordRaw> =
+            ASPRRecordIterator::from_path(PathBuf::from("malformed_work_id.csv"))
+                .unwrap()
+                .with_raw_fields()
+                .collect();
+        assert_eq!(records.len(), 2);
+
+        // The malformed work id is `None` in the parsed record but retained as text.
+        assert!(records[0].record.home_id.is_some());
+        assert!(records[0].record.work_id.is_none());
+        assert_eq!(records[0].work_id, "11001006202O1546");
+
+        assert!(records[1].record.work_id.is_some());
+        assert_eq!(records[1].work_id, "1100100620201546");
+        assert_eq!(records[1].school_id, "");
+    }
+
+    #[test]
+    fn test_annotations_preserved() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+        let path = PathBuf::from("annotated_ids.csv");
+
+        let records: Vec<ASPRPersonRecordAnnotated> = ASPRRecordIterator::from_path(path.clone())
+            .unwrap()
+            .with_annotations()
+            .collect();
+        assert_eq!(records.len(), 2);
+
+        assert!(records[0].record.home_id.is_some());
+        assert!(records[0].record.work_id.is_some());
+        assert_eq!(records[0].home_rest, "|urban");
+        assert_eq!(records[0].school_rest, "");
+        assert_eq!(records[0].work_rest, "|checked");
+
+        assert!(records[1].record.school_id.is_some());
+        assert_eq!(records[1].home_rest, "");
+        assert_eq!(records[1].school_rest, " #note");
+
+        // Without `with_annotations`, annotated ids fail to parse.
+        let plain: Vec<ASPRPersonRecord> = ASPRRecordIterator::from_path(path).unwrap().collect();
+        assert!(plain[0].home_id.is_none());
+        assert_eq!(plain[0].age, records[0].record.age);
+    }
+
+    #[test]
+    fn test_invalid_utf8_line() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+        let path = PathBuf::from("invalid_utf8.csv");
+
+        // The unchecked iterator stops at the corrupt line.
+        assert_eq!(
+            ASPRRecordIterator::from_path(path.clone()).unwrap().count(),
+            1
+        );
+
+        // The checked iterator reports it and continues.
+        let results: Vec<Result<ASPRPersonRecord, ASPRError>> = ASPRRecordIterator::from_path(path)
+            .unwrap()
+            .checked()
+            .collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().age, 34);
+        assert!(matches!(&results[1], Err(ASPRError::Io(e)) if e.kind() == ErrorKind::InvalidData));
+        assert_eq!(results[2].as_ref().unwrap().age, 12);
+    }
+
+    #[test]
+    fn test_short_row() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+        let path = PathBuf::from("short_row.csv");
+
+        // A truncated row in the middle of the file is reported, not mistaken for the end of the file.
+        let results: Vec<Result<ASPRPersonRecord, ASPRError>> =
+            ASPRRecordIterator::from_path(path.clone())
+                .unwrap()
+                .checked()
+                .collect();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            &results[1],
+            Err(ASPRError::MalformedRow {
+                line: 3,
+                expected: ASPR_COLUMN_COUNT,
+                found: 2,
+                ..
+            })
+        ));
+        assert_eq!(results[2].as_ref().unwrap().age, 12);
+
+        let results: Vec<Result<ASPRPersonRecord, ASPRError>> =
+            ASPRRecordIterator::from_file_iterator_checked(std::iter::once(path.clone())).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_err());
+
+        let ages: Vec<u8> = ASPRRecordIterator::from_path(path)
+            .unwrap()
+            .map(|record| record.age)
+            .collect();
+        assert_eq!(ages, vec![41, 12]);
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("34", AgePolicy::Reject).unwrap(), 34);
+        assert_eq!(parse_age(" 120 ", AgePolicy::Clamp).unwrap(), 120);
+
+        // Overflowing age
+        assert!(matches!(
+            parse_age("999", AgePolicy::Reject),
+            Err(ASPRError::Parse(FIPSParserError::ValueExceedsCapacity {
+                value: 999,
+                capacity: 255
+            }))
+        ));
+        assert_eq!(parse_age("999", AgePolicy::Clamp).unwrap(), u8::MAX);
+
+        // Non-numeric age
+        for policy in [AgePolicy::Reject, AgePolicy::Clamp] {
+            assert!(matches!(
+                parse_age("abc", policy),
+                Err(ASPRError::Parse(FIPSParserError::InvalidDigit { found: 'a' }))
+            ));
+            assert!(matches!(
+                parse_age("12a", policy),
+                Err(ASPRError::Parse(FIPSParserError::InvalidDigit { found: 'a' }))
+            ));
+            assert!(matches!(
+                parse_age("", policy),
+                Err(ASPRError::Parse(FIPSParserError::Empty))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_age() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path());
+        let path = PathBuf::from("out_of_range_age.csv");
+
+        let results: Vec<Result<ASPRPersonRecord, ASPRError>> =
+            ASPRRecordIterator::from_path(path.clone())
+                .unwrap()
+                .checked()
+                .collect();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            &results[1],
+            Err(ASPRError::Field {
+                line: 3,
+                field: "age",
+                offset: 0,
+                error: FIPSParserError::ValueExceedsCapacity { .. },
+                ..
+            })
+        ));
+        assert_eq!(results[2].as_ref().unwrap().age, 12);
+
+        let ages: Vec<u8> = ASPRRecordIterator::from_path(path.clone())
+            .unwrap()
+            .with_age_policy(AgePolicy::Clamp)
+            .map(|record| record.age)
+            .collect();
+        assert_eq!(ages, vec![34, 255, 12]);
+
+        // Under the default policy, the unchecked iterators skip the row and continue with the next one.
+        let ages: Vec<u8> = ASPRRecordIterator::from_path(path.clone())
+            .unwrap()
+            .map(|record| record.age)
+            .collect();
+        assert_eq!(ages, vec![34, 12]);
+        let ages: Vec<u8> = ASPRRecordIterator::from_path(path)
+            .unwrap()
+            .with_raw_fields()
+            .map(|record| record.record.age)
+            .collect();
+        assert_eq!(ages, vec![34, 12]);
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("age,homeId,schoolId,workplaceId"), ',');
+        assert_eq!(detect_delimiter("age\thomeId\tschoolId\tworkplaceId"), '\t');
+    }
+
+    #[test]
+    fn test_iter_csv_files_many() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path().join("ASPR_Synthetic_Population"));
+
+        let ak = format!("{}/AK", CBSA_ALL_DIR);
+        let tx = format!("{}/TX", CBSA_ALL_DIR);
+        let files = iter_csv_files_many(&[&ak, &tx], false).unwrap();
+        let mut file_names: Vec<_> = files
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        file_names.sort_unstable();
+        assert_eq!(file_names, vec!["Ketchikan AK.csv", "Vernon TX.csv"]);
+
+        let missing = format!("{}/ZZ", CBSA_ALL_DIR);
+        assert!(iter_csv_files_many(&[&ak, &missing], false).is_err());
+        assert_eq!(
+            iter_csv_files_many(&[&ak, &missing], true).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_count_records() {
+        let _guard = TEST_MUTEX.lock();
+
+        // Files with and without a header row
+        set_aspr_data_path(test_data_path());
+        for file_name in ["sorted_by_home.csv", "no_header.csv", "tab_separated.tsv"] {
+            let path = PathBuf::from(file_name);
+            assert_eq!(
+                count_records(path.clone()).unwrap(),
+                ASPRRecordIterator::from_path(path).unwrap().count()
+            );
+        }
+
+        set_aspr_data_path(test_data_path().join("ASPR_Synthetic_Population"));
+        let tx = PathBuf::from(ALL_STATES_DIR).join("tx.csv");
+        assert_eq!(
+            count_records(tx.clone()).unwrap(),
+            ASPRRecordIterator::from_path(tx).unwrap().count()
+        );
+        assert!(count_records(PathBuf::from("missing.csv")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_count_records_non_utf8_zip_path() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+        let _guard = TEST_MUTEX.lock();
+        // The path is rejected before the archive is opened, so the archive need not exist.
+        set_aspr_data_path(test_data_path().join("missing.zip"));
+
+        let file_path = PathBuf::from(OsStr::from_bytes(b"all_states/\xff.csv"));
+        assert!(matches!(
+            count_records(file_path.clone()),
+            Err(ASPRError::FileIo { path, error })
+                if path == file_path && error.kind() == ErrorKind::InvalidInput
+        ));
+    }
+
+    #[test]
+    fn test_count_records_per_file() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path().join("ASPR_Synthetic_Population"));
+
+        let ak = PathBuf::from(CBSA_ALL_DIR).join("AK/Ketchikan AK.csv");
+        let tx = PathBuf::from(CBSA_ALL_DIR).join("TX/Vernon TX.csv");
+        let counts = count_records_per_file([ak.clone(), tx.clone()]).unwrap();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&ak], 2);
+        assert_eq!(counts[&tx], 3);
+
+        let missing = PathBuf::from(CBSA_ALL_DIR).join("ZZ/Nowhere ZZ.csv");
+        assert!(count_records_per_file([missing]).is_err());
+    }
+
+    #[test]
+    fn test_classify_cbsa_path() {
+        let cbsa_all = Path::new(CBSA_ALL_DIR);
+        assert_eq!(
+            classify_cbsa_path(&cbsa_all.join("AK/Ketchikan AK.csv")),
+            Some(CbsaKind::SingleState)
+        );
+        assert_eq!(
+            classify_cbsa_path(&cbsa_all.join(MULTI_STATE_DIR).join("Texarkana TX-AR.csv")),
+            Some(CbsaKind::MultiState)
+        );
+        assert_eq!(
+            classify_cbsa_path(&cbsa_all.join(NON_CBSA_RESIDENTS_DIR).join("ak.csv")),
+            Some(CbsaKind::NonCbsaResidents)
+        );
+
+        // Paths that include the ASPR data path, and the other CBSA layout
+        assert_eq!(
+            classify_cbsa_path(
+                &test_data_path()
+                    .join("ASPR_Synthetic_Population")
+                    .join(CBSA_ONLY_RESIDENTS_DIR)
+                    .join("TX/Vernon TX.csv")
+            ),
+            Some(CbsaKind::SingleState)
+        );
+
+        // Not in a subdirectory of a CBSA layout directory
+        assert_eq!(
+            classify_cbsa_path(&Path::new(ALL_STATES_DIR).join("ak.csv")),
+            None
+        );
+        assert_eq!(
+            classify_cbsa_path(&Path::new(CBSA_ONLY_RESIDENTS_DIR).join("ak.csv")),
+            None
+        );
+        assert_eq!(classify_cbsa_path(Path::new(CBSA_ALL_DIR)), None);
+    }
+
+    #[test]
+    fn test_available_subdirectories() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path().join("ASPR_Synthetic_Population"));
+
+        assert_eq!(
+            available_subdirectories(AsprLayout::CbsaAll).unwrap(),
+            vec!["AK", MULTI_STATE_DIR, "TX", NON_CBSA_RESIDENTS_DIR]
+        );
+        assert_eq!(
+            available_subdirectories(AsprLayout::CbsaOnlyResidents).unwrap(),
+            vec!["AK", "TX"]
+        );
+        assert!(available_subdirectories(AsprLayout::AllStates)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_total_record_count() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path().join("ASPR_Synthetic_Population"));
+
+        assert_eq!(total_record_count("").unwrap(), 19);
+        assert_eq!(total_record_count(ALL_STATES_DIR).unwrap(), 7);
+        // Includes the nested `Multi-state` and `non_CBSA_residents` directories
+        assert_eq!(total_record_count(CBSA_ALL_DIR).unwrap(), 8);
+        assert!(total_record_count("missing").is_err());
+
+        // A relative data path, resolved against the crate directory in which tests run
+        set_aspr_data_path(PathBuf::from("test_data/ASPR_Synthetic_Population"));
+        assert_eq!(total_record_count("").unwrap(), 19);
+        assert_eq!(total_record_count(CBSA_ALL_DIR).unwrap(), 8);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_total_record_count_symlink_cycle() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path().join("ASPR_Synthetic_Population"));
+        let expected = count_records(PathBuf::from(ALL_STATES_DIR).join("tx.csv")).unwrap() as u64;
+
+        // A data directory containing a symlink to itself
+        let root =
+            std::env::temp_dir().join(format!("ixa_aspr_symlink_cycle_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::copy(
+            test_data_path().join("ASPR_Synthetic_Population/all_states/tx.csv"),
+            root.join("tx.csv"),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(&root, root.join("loop")).unwrap();
+
+        set_aspr_data_path(root.clone());
+        let count = total_record_count("");
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(count.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_iter_csv_files_runtime_subdirectory() {
+        let _guard = TEST_MUTEX.lock();
+        set_aspr_data_path(test_data_path().join("ASPR_Synthetic_Population"));
+
+        // Subdirectory names computed at runtime
+        let subdirectory: String = format!("{}/{}", CBSA_ALL_DIR, USState::TX.as_ref());
+        let files: Vec<PathBuf> = iter_csv_files(&subdirectory).unwrap().collect();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].ends_with("Vernon TX.csv"));
+
+        let subdirectory = PathBuf::from(CBSA_ONLY_RESIDENTS_DIR).join(USState::AK.as_ref());
+        assert_eq!(iter_csv_files(subdirectory).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_normalize_header() {
+        assert_eq!(
+            normalize_header("\u{feff}age,homeId,schoolId,workplaceId\r"),
+            "age,homeId,schoolId,workplaceId"
+        );
+        assert_eq!(normalize_header("age,homeId  "), "age,homeId");
+    }
+
     #[cfg(feature = "aspr_dataset_tests")]
     #[test]
     fn test_record_iterator_state_population() {
@@ -413,7 +1911,7 @@ mod tests {
     fn test_zip_record_iterator_state_population() {
         let _guard = TEST_MUTEX.lock();
 
-        set_aspr_data_path(get_aspr_data_path().with_extension("zip"));
+        set_aspr_data_path(PathBuf::from(DEFAULT_ASPR_DATA_PATH).with_extension("zip"));
         println!("USING PATH {:?}", get_aspr_data_path());
 
         let records = match ASPRRecordIterator::state_population(USState::WY) {
@@ -433,7 +1931,7 @@ mod tests {
     fn test_zip_record_iterator_from_path() {
         let _guard = TEST_MUTEX.lock();
 
-        set_aspr_data_path(get_aspr_data_path().with_extension("zip"));
+        set_aspr_data_path(PathBuf::from(DEFAULT_ASPR_DATA_PATH).with_extension("zip"));
 
         let path = PathBuf::from(CBSA_ALL_DIR).join("AK/Ketchikan AK.csv");
         let records = ASPRRecordIterator::from_path(path).unwrap();
@@ -447,7 +1945,7 @@ mod tests {
     fn test_zip_record_iterator_from_files() {
         let _guard = TEST_MUTEX.lock();
 
-        set_aspr_data_path(get_aspr_data_path().with_extension("zip"));
+        set_aspr_data_path(PathBuf::from(DEFAULT_ASPR_DATA_PATH).with_extension("zip"));
 
         let all_path = PathBuf::from(CBSA_ALL_DIR);
         let only_residents_path = PathBuf::from(CBSA_ONLY_RESIDENTS_DIR);
@@ -471,7 +1969,7 @@ mod tests {
     fn test_zip_state_row_iter() {
         let _guard = TEST_MUTEX.lock();
 
-        set_aspr_data_path(get_aspr_data_path().with_extension("zip"));
+        set_aspr_data_path(PathBuf::from(DEFAULT_ASPR_DATA_PATH).with_extension("zip"));
 
         let state = USState::AL;
         let state_records = ASPRRecordIterator::state_population(state).unwrap();
@@ -0,0 +1,114 @@
+/*!
+
+`deserialize_with` adapters for deserializing the id fields of an [`ASPRPersonRecord`](crate::ASPRPersonRecord) from
+strings, enabled with the "serde" feature. Each adapter parses its field with the corresponding FIPS parser of the
+[`parser`](crate::parser) module. As in the data files, an empty field is an absent id. A nonempty field that fails to
+parse is a deserialization error, so that with the `csv` crate
+
+```ignore
+let records = csv::Reader::from_path(path)?.deserialize::<ASPRPersonRecord>();
+```
+
+yields an error for each malformed row rather than silently dropping its ids.
+
+*/
+
+use crate::parser::{
+    parse_fips_home_id, parse_fips_school_id, parse_fips_workplace_id, FIPSParseResult,
+};
+use ixa_fips::FIPSCode;
+use serde::{de::Error, Deserialize, Deserializer};
+
+/// Deserializes a `homeId` field, e.g. `"110010109000024"`.
+pub fn home_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<FIPSCode>, D::Error> {
+    deserialize_id(deserializer, parse_fips_home_id)
+}
+
+/// Deserializes a `schoolId` field, either a public school id, e.g. `"11001009810157"`, or a private school id, e.g.
+/// `"11001xprvx0042"`.
+pub fn school_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<FIPSCode>, D::Error> {
+    deserialize_id(deserializer, parse_fips_school_id)
+}
+
+/// Deserializes a `workplaceId` field, e.g. `"1100101090000123"`.
+pub fn work_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<FIPSCode>, D::Error> {
+    deserialize_id(deserializer, parse_fips_workplace_id)
+}
+
+/// Deserializes a string and parses it with `parser`, trimmed of surrounding whitespace as by the record iterators of the
+/// `archive` module. An empty or all-whitespace string is `None`.
+fn deserialize_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    parser: fn(&str) -> FIPSParseResult<FIPSCode>,
+) -> Result<Option<FIPSCode>, D::Error> {
+    // Borrowing would fail for input that must be unescaped, so take ownership.
+    let id_str = String::deserialize(deserializer)?;
+    let id_str = id_str.trim();
+    if id_str.is_empty() {
+        return Ok(None);
+    }
+    parser(id_str)
+        .map(|(_, id)| Some(id))
+        .map_err(|(_, error)| D::Error::custom(format!("invalid id {:?}: {}", id_str, error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parser::{parse_fips_home_id, parse_fips_school_id},
+        ASPRPersonRecord,
+    };
+
+    #[test]
+    fn test_deserialize_csv_row() {
+        let data = "age,homeId,schoolId,workplaceId\n\
+                    7,110010109000024,11001xprvx0042,\n\
+                    35,110010109000024,,1100101090000123\n";
+        let mut reader = csv::Reader::from_reader(data.as_bytes());
+        let records: Vec<ASPRPersonRecord> =
+            reader.deserialize().collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].age, 7);
+        assert_eq!(
+            records[0].home_id,
+            Some(parse_fips_home_id("110010109000024").unwrap().1)
+        );
+        assert_eq!(
+            records[0].school_id,
+            Some(parse_fips_school_id("11001xprvx0042").unwrap().1)
+        );
+        assert_eq!(records[0].work_id, None);
+        assert_eq!(records[1].age, 35);
+        assert_eq!(records[1].school_id, None);
+        assert!(records[1].work_id.is_some());
+    }
+
+    #[test]
+    fn test_deserialize_untrimmed_fields() {
+        let data = "age,homeId,schoolId,workplaceId\n\
+                    7, 110010109000024,11001009810157 ,  \n";
+        let mut reader = csv::Reader::from_reader(data.as_bytes());
+        let records: Vec<ASPRPersonRecord> =
+            reader.deserialize().collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(
+            records[0].home_id,
+            Some(parse_fips_home_id("110010109000024").unwrap().1)
+        );
+        assert_eq!(
+            records[0].school_id,
+            Some(parse_fips_school_id("11001009810157").unwrap().1)
+        );
+        assert_eq!(records[0].work_id, None);
+    }
+
+    #[test]
+    fn test_deserialize_invalid_id() {
+        let data = "age,homeId,schoolId,workplaceId\n7,1100101090000X4,,\n";
+        let mut reader = csv::Reader::from_reader(data.as_bytes());
+        let result = reader.deserialize::<ASPRPersonRecord>().next().unwrap();
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("1100101090000X4"), "{error}");
+    }
+}
@@ -1,4 +1,4 @@
-use ixa_fips::parser::FIPSParserError;
+use ixa_fips::{parser::FIPSParserError, FIPSCode};
 use std::{
     error::Error,
     fmt::{Debug, Display},
@@ -10,8 +10,44 @@ use zip::result::ZipError;
 
 pub enum ASPRError {
     Io(IoError),
+    /// An I/O error opening or reading the file at `path`. The path of a data file is relative to the ASPR data path.
+    FileIo {
+        path: PathBuf,
+        error: IoError,
+    },
     Parse(FIPSParserError),
     EmptyFile(PathBuf),
+    /// The header row of the data file has fewer columns than an ASPR record requires.
+    MalformedHeader {
+        path: PathBuf,
+        expected: usize,
+        found: usize,
+    },
+    /// A data row has fewer fields than an ASPR record requires. `line` is the 1-based line number within the file
+    /// (counting the header row).
+    MalformedRow {
+        path: PathBuf,
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// An id violates an invariant of the ASPR id format, e.g. a private school id with a census tract.
+    InvalidId {
+        id: FIPSCode,
+        reason: &'static str,
+    },
+    /// A field of a data row failed to parse. `path` is the path of the data file relative to the ASPR data path, `line`
+    /// the 1-based line number within the file (counting the header row), `column` the 0-based index of the field among
+    /// the columns of the file, and `field` its column name, e.g. `"workplaceId"`. `offset` is the byte offset within
+    /// the line at which parsing failed, e.g. of the first invalid digit.
+    Field {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        field: &'static str,
+        offset: usize,
+        error: FIPSParserError,
+    },
     #[cfg(feature = "aspr_archive")]
     ZipError(ZipError),
 }
@@ -20,8 +56,53 @@ impl Display for ASPRError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ASPRError::Io(e) => write!(f, "ASPR IO error: {}", e),
+            ASPRError::FileIo { path, error } => {
+                write!(f, "ASPR IO error in {}: {}", path.display(), error)
+            }
             ASPRError::Parse(e) => write!(f, "ASPR Parse error: {}", e),
             ASPRError::EmptyFile(path) => write!(f, "ASPR data file is empty: {}", path.display()),
+            ASPRError::MalformedHeader {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "ASPR data file header has {} columns, expected at least {}: {}",
+                found,
+                expected,
+                path.display()
+            ),
+            ASPRError::MalformedRow {
+                path,
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "ASPR data row has {} fields, expected at least {}: {}, line {}",
+                found,
+                expected,
+                path.display(),
+                line
+            ),
+            ASPRError::InvalidId { id, reason } => write!(f, "Invalid ASPR id {}: {}", id, reason),
+            ASPRError::Field {
+                path,
+                line,
+                column,
+                field,
+                offset,
+                error,
+            } => write!(
+                f,
+                "ASPR Parse error in {}, line {}, column {} ({}) at byte {}: {}",
+                path.display(),
+                line,
+                column,
+                field,
+                offset,
+                error
+            ),
             #[cfg(feature = "aspr_archive")]
             ASPRError::ZipError(e) => write!(f, "ASPR Zip error: {}", e),
         }
@@ -37,9 +118,12 @@ impl Debug for ASPRError {
 impl Error for ASPRError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
-            ASPRError::Io(e) => Some(e),
-            ASPRError::Parse(e) => Some(e),
-            ASPRError::EmptyFile(_) => None,
+            ASPRError::Io(e) | ASPRError::FileIo { error: e, .. } => Some(e),
+            ASPRError::Parse(e) | ASPRError::Field { error: e, .. } => Some(e),
+            ASPRError::EmptyFile(_)
+            | ASPRError::MalformedHeader { .. }
+            | ASPRError::MalformedRow { .. }
+            | ASPRError::InvalidId { .. } => None,
             #[cfg(feature = "aspr_archive")]
             ASPRError::ZipError(e) => Some(e),
         }
@@ -0,0 +1,162 @@
+//! A columnar representation of ASPR person records for exploratory analysis.
+//!
+//! With the `polars` feature enabled, a `RecordFrame` can be converted to a polars `DataFrame` with
+//! `RecordFrame::to_polars`.
+
+use crate::ASPRPersonRecord;
+
+/// Stores ASPR person records as one column per field. Ids are stored as the raw 64-bit encoding of their
+/// `FIPSCode` (see `impl From<FIPSCode> for u64` in `ixa_fips`), with `None` for missing or unparsable ids. Build one by collecting
+/// an iterator of records:
+///
+/// ```ignore
+/// # use ixa_aspr::{archive::ASPRRecordIterator, frame::RecordFrame};
+/// # use ixa_fips::USState;
+/// let frame: RecordFrame = ASPRRecordIterator::state_population(USState::AK).unwrap().collect();
+/// ```
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct RecordFrame {
+    ages: Vec<u8>,
+    home_ids: Vec<Option<u64>>,
+    school_ids: Vec<Option<u64>>,
+    work_ids: Vec<Option<u64>>,
+}
+
+impl RecordFrame {
+    /// The names of the columns, in the same order as the columns of an ASPR CSV file.
+    pub const COLUMN_NAMES: [&'static str; 4] = ["age", "homeId", "schoolId", "workplaceId"];
+
+    /// Returns the age column.
+    #[must_use]
+    pub fn ages(&self) -> &[u8] {
+        &self.ages
+    }
+
+    /// Returns the home id column.
+    #[must_use]
+    pub fn home_ids(&self) -> &[Option<u64>] {
+        &self.home_ids
+    }
+
+    /// Returns the school id column.
+    #[must_use]
+    pub fn school_ids(&self) -> &[Option<u64>] {
+        &self.school_ids
+    }
+
+    /// Returns the workplace id column.
+    #[must_use]
+    pub fn work_ids(&self) -> &[Option<u64>] {
+        &self.work_ids
+    }
+
+    /// Returns the number of rows.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ages.len()
+    }
+
+    /// Returns `true` if the frame has no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ages.is_empty()
+    }
+
+    /// Appends `record` as a new row.
+    pub fn push(&mut self, record: ASPRPersonRecord) {
+        self.ages.push(record.age);
+        self.home_ids.push(record.home_id.map(u64::from));
+        self.school_ids.push(record.school_id.map(u64::from));
+        self.work_ids.push(record.work_id.map(u64::from));
+    }
+
+    /// Converts the frame to a polars `DataFrame` with the columns named in `COLUMN_NAMES`.
+    #[cfg(feature = "polars")]
+    pub fn to_polars(&self) -> polars::prelude::PolarsResult<polars::prelude::DataFrame> {
+        use polars::prelude::{Column, DataFrame};
+
+        let [age, home_id, school_id, work_id] = Self::COLUMN_NAMES;
+        DataFrame::new(vec![
+            Column::new(age.into(), &self.ages),
+            Column::new(home_id.into(), &self.home_ids),
+            Column::new(school_id.into(), &self.school_ids),
+            Column::new(work_id.into(), &self.work_ids),
+        ])
+    }
+}
+
+impl FromIterator<ASPRPersonRecord> for RecordFrame {
+    fn from_iter<T: IntoIterator<Item = ASPRPersonRecord>>(iter: T) -> Self {
+        let mut frame = RecordFrame::default();
+        frame.extend(iter);
+        frame
+    }
+}
+
+impl Extend<ASPRPersonRecord> for RecordFrame {
+    fn extend<T: IntoIterator<Item = ASPRPersonRecord>>(&mut self, iter: T) {
+        for record in iter {
+            self.push(record);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_fips_home_id, parse_fips_school_id, parse_fips_workplace_id};
+
+    fn synthetic_records() -> Vec<ASPRPersonRecord> {
+        let (_, home_id) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, school_id) = parse_fips_school_id("11001009810157").unwrap();
+        let (_, work_id) = parse_fips_workplace_id("1100100620201546").unwrap();
+
+        vec![
+            ASPRPersonRecord {
+                age: 41,
+                home_id: Some(home_id),
+                school_id: None,
+                work_id: Some(work_id),
+            },
+            ASPRPersonRecord {
+                age: 9,
+                home_id: Some(home_id),
+                school_id: Some(school_id),
+                work_id: None,
+            },
+            ASPRPersonRecord {
+                age: 70,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_collect_record_frame() {
+        let records = synthetic_records();
+        let frame: RecordFrame = records.iter().copied().collect();
+
+        assert_eq!(frame.len(), 3);
+        assert_eq!(frame.home_ids().len(), 3);
+        assert_eq!(frame.school_ids().len(), 3);
+        assert_eq!(frame.work_ids().len(), 3);
+
+        assert_eq!(frame.ages(), &[41, 9, 70]);
+        assert_eq!(frame.home_ids()[0], records[0].home_id.map(u64::from));
+        assert_eq!(
+            frame.school_ids(),
+            &[None, records[1].school_id.map(u64::from), None]
+        );
+        assert_eq!(frame.work_ids()[2], None);
+    }
+
+    #[cfg(feature = "polars")]
+    #[test]
+    fn test_to_polars() {
+        let frame: RecordFrame = synthetic_records().into_iter().collect();
+        let df = frame.to_polars().unwrap();
+
+        assert_eq!(df.shape(), (3, 4));
+        assert_eq!(df.column("schoolId").unwrap().null_count(), 2);
+    }
+}
@@ -0,0 +1,184 @@
+//! A streaming join of two record streams on the person key of `ASPRPersonRecord::same_person`, e.g. to enrich the
+//! records of one version of a dataset with the fields of another.
+
+use crate::ASPRPersonRecord;
+use std::{cmp::Ordering, collections::VecDeque, iter::Peekable};
+
+/// Which records without a match in the other stream `join_records` yields.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default, Debug)]
+pub enum JoinMode {
+    /// Only matched pairs
+    #[default]
+    Inner,
+    /// Matched pairs and unmatched records of the left stream
+    Left,
+    /// Matched pairs and unmatched records of both streams
+    Outer,
+}
+
+/// An item of `join_records`: a matched pair, or a record of one stream without a match in the other.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum JoinedRecord {
+    Both(ASPRPersonRecord, ASPRPersonRecord),
+    Left(ASPRPersonRecord),
+    Right(ASPRPersonRecord),
+}
+
+/// Joins `left` and `right` on the person key of `ASPRPersonRecord::person_key`, pairing records for which
+/// `ASPRPersonRecord::same_person` holds, in a single pass over both streams.
+///
+/// Both streams must be sorted by `ASPRPersonRecord::person_key`, that is, by home id in hierarchical order and then by
+/// age, for example with `records.sort_by_key(ASPRPersonRecord::person_key)`. This is not checked; if a stream is not
+/// sorted, records that should match may be reported as unmatched.
+///
+/// When several records of each stream share a key, as for twins, every pair of them is yielded. Records without a home
+/// id never match. Unmatched records are yielded as `JoinedRecord::Left` or `JoinedRecord::Right` according to `mode`,
+/// in key order among the matched pairs.
+pub fn join_records<L, R>(left: L, right: R, mode: JoinMode) -> impl Iterator<Item = JoinedRecord>
+where
+    L: IntoIterator<Item = ASPRPersonRecord>,
+    R: IntoIterator<Item = ASPRPersonRecord>,
+{
+    RecordJoin {
+        left: left.into_iter().peekable(),
+        right: right.into_iter().peekable(),
+        mode,
+        pending: VecDeque::new(),
+    }
+}
+
+struct RecordJoin<L: Iterator<Item = ASPRPersonRecord>, R: Iterator<Item = ASPRPersonRecord>> {
+    left: Peekable<L>,
+    right: Peekable<R>,
+    mode: JoinMode,
+    /// The pairs of a run of matching records not yet yielded
+    pending: VecDeque<JoinedRecord>,
+}
+
+impl<L, R> Iterator for RecordJoin<L, R>
+where
+    L: Iterator<Item = ASPRPersonRecord>,
+    R: Iterator<Item = ASPRPersonRecord>,
+{
+    type Item = JoinedRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(joined) = self.pending.pop_front() {
+                return Some(joined);
+            }
+
+            let ordering = match (self.left.peek(), self.right.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                // A record without a home id has the least key but matches nothing, so it is treated as unmatched.
+                (Some(left), Some(_)) if left.home_id.is_none() => Ordering::Less,
+                (Some(left), Some(right)) => left.person_key().cmp(&right.person_key()),
+            };
+
+            match ordering {
+                Ordering::Less => {
+                    let left = self.left.next().unwrap();
+                    if self.mode != JoinMode::Inner {
+                        return Some(JoinedRecord::Left(left));
+                    }
+                }
+                Ordering::Greater => {
+                    let right = self.right.next().unwrap();
+                    if self.mode == JoinMode::Outer {
+                        return Some(JoinedRecord::Right(right));
+                    }
+                }
+                Ordering::Equal => {
+                    let key = self.left.peek().unwrap().person_key();
+                    let mut lefts = vec![];
+                    while let Some(left) = self.left.next_if(|left| left.person_key() == key) {
+                        lefts.push(left);
+                    }
+                    while let Some(right) = self.right.next_if(|right| right.person_key() == key) {
+                        self.pending
+                            .extend(lefts.iter().map(|&left| JoinedRecord::Both(left, right)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_fips_home_id, parse_fips_workplace_id};
+
+    fn record(age: u8, home_id: Option<&str>) -> ASPRPersonRecord {
+        ASPRPersonRecord {
+            age,
+            home_id: home_id.map(|id| parse_fips_home_id(id).unwrap().1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_join_records() {
+        let (_, work_id) = parse_fips_workplace_id("1100100620201546").unwrap();
+        let with_work = |record: ASPRPersonRecord| ASPRPersonRecord {
+            work_id: Some(work_id),
+            ..record
+        };
+
+        let a = record(41, Some("110010109000024"));
+        let b = record(7, Some("110010109000025"));
+        let twin = record(3, Some("482012231000001"));
+        let left_only = record(39, Some("110010109000024"));
+        let right_only = record(66, Some("560010001000001"));
+        let no_home = record(52, None);
+
+        let mut left = vec![a, b, twin, twin, left_only, no_home];
+        let mut right = vec![
+            with_work(a),
+            with_work(b),
+            with_work(twin),
+            right_only,
+            no_home,
+        ];
+        left.sort_by_key(ASPRPersonRecord::person_key);
+        right.sort_by_key(ASPRPersonRecord::person_key);
+
+        let inner: Vec<JoinedRecord> =
+            join_records(left.clone(), right.clone(), JoinMode::Inner).collect();
+        assert_eq!(
+            inner,
+            vec![
+                JoinedRecord::Both(a, with_work(a)),
+                JoinedRecord::Both(b, with_work(b)),
+                JoinedRecord::Both(twin, with_work(twin)),
+                JoinedRecord::Both(twin, with_work(twin)),
+            ]
+        );
+
+        let outer: Vec<JoinedRecord> =
+            join_records(left.clone(), right.clone(), JoinMode::Outer).collect();
+        assert_eq!(
+            outer,
+            vec![
+                JoinedRecord::Left(no_home),
+                JoinedRecord::Right(no_home),
+                JoinedRecord::Left(left_only),
+                JoinedRecord::Both(a, with_work(a)),
+                JoinedRecord::Both(b, with_work(b)),
+                JoinedRecord::Both(twin, with_work(twin)),
+                JoinedRecord::Both(twin, with_work(twin)),
+                JoinedRecord::Right(right_only),
+            ]
+        );
+
+        // A left join yields the same as the outer join, less the unmatched records of the right stream.
+        let left_join: Vec<JoinedRecord> = join_records(left, right, JoinMode::Left).collect();
+        let expected: Vec<JoinedRecord> = outer
+            .into_iter()
+            .filter(|joined| !matches!(joined, JoinedRecord::Right(_)))
+            .collect();
+        assert_eq!(left_join, expected);
+    }
+}
@@ -23,23 +23,50 @@ re-exported for convenience.
 */
 #![allow(dead_code)]
 
-use std::fmt::{Display, Write};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt::{Display, Write},
+};
 
 pub use ixa_fips as fips;
-use fips::FIPSCode;
+use fips::{FIPSCode, IdCode, StateSet, USState};
+use errors::ASPRError;
 
 // Re-exported publicly in `parser.rs`.
 #[cfg(feature = "aspr_archive")]
 pub mod archive;
+#[cfg(feature = "serde")]
+pub mod deserialize;
 pub mod errors;
+pub mod frame;
+pub mod join;
 pub mod parser;
+pub mod population;
+pub mod writer;
 
 /// A record representing a person in the ASPR synthetic population dataset.
+///
+/// With the "serde" feature, a record deserializes from a row with the columns of the dataset, `age`, `homeId`,
+/// `schoolId`, and `workplaceId`, the ids being parsed as in the [`deserialize`] module.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct ASPRPersonRecord {
     pub age: u8,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "homeId", deserialize_with = "deserialize::home_id")
+    )]
     pub home_id: Option<FIPSCode>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "schoolId", deserialize_with = "deserialize::school_id")
+    )]
     pub school_id: Option<FIPSCode>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "workplaceId", deserialize_with = "deserialize::work_id")
+    )]
     pub work_id: Option<FIPSCode>,
 }
 
@@ -61,6 +88,244 @@ impl Display for ASPRPersonRecord {
     }
 }
 
+/// One of the id fields of an `ASPRPersonRecord`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RecordSlot {
+    Home,
+    School,
+    Work,
+}
+
+impl RecordSlot {
+    /// Every slot, in the order of the columns of a data file
+    pub const ALL: [RecordSlot; 3] = [RecordSlot::Home, RecordSlot::School, RecordSlot::Work];
+}
+
+impl ASPRPersonRecord {
+    /// Checks each id of the record with `validate_aspr_id`, and that its setting category is one of
+    /// `ASPRPersonRecord::expected_categories` for its slot.
+    pub fn validate(&self) -> Result<(), ASPRError> {
+        for slot in RecordSlot::ALL {
+            let Some(id) = self.id(slot) else {
+                continue;
+            };
+            validate_aspr_id(id)?;
+            let expected = Self::expected_categories(slot);
+            let category = SettingCategory::decode(id.category_code());
+            if !category.is_some_and(|category| expected.contains(&category)) {
+                return Err(ASPRError::InvalidId {
+                    id,
+                    reason: "the setting category does not match the field of the record",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the id in `slot`, if any.
+    #[must_use]
+    pub fn id(&self, slot: RecordSlot) -> Option<FIPSCode> {
+        match slot {
+            RecordSlot::Home => self.home_id,
+            RecordSlot::School => self.school_id,
+            RecordSlot::Work => self.work_id,
+        }
+    }
+
+    /// Returns the setting categories an id in `slot` may have, as assigned by the parsers of the `parser` module. A
+    /// school id may be of a public or a private school, so the school slot has two.
+    #[must_use]
+    pub fn expected_categories(slot: RecordSlot) -> &'static [SettingCategory] {
+        match slot {
+            RecordSlot::Home => &[SettingCategory::Home],
+            RecordSlot::School => &[
+                SettingCategory::PublicSchool,
+                SettingCategory::PrivateSchool,
+            ],
+            RecordSlot::Work => &[SettingCategory::Workplace],
+        }
+    }
+
+    /// Returns `true` if `self` and `other` are taken to describe the same person in two versions of a dataset, that is,
+    /// if they have the same age and home id. School and work ids may differ between versions, so they are ignored. A
+    /// record without a home id cannot be matched this way, so it is not the same person as any record.
+    #[must_use]
+    pub fn same_person(&self, other: &Self) -> bool {
+        self.age == other.age && self.home_id.is_some() && self.home_id == other.home_id
+    }
+
+    /// Returns the key compared by `same_person`, the home id and then the age, for sorting records so that records of
+    /// the same person are adjacent, as `join::join_records` requires.
+    #[must_use]
+    pub fn person_key(&self) -> (Option<FIPSCode>, u8) {
+        (self.home_id, self.age)
+    }
+}
+
+/// Checks the invariants of the ASPR id format that the encoding of `FIPSCode` cannot enforce by itself: the category
+/// must be a `SettingCategory`, the id must fit in the digits the format gives that category (see
+/// `SettingCategory::max_id`), and a private school id, which is scoped to its county, must have census tract 0. Returns
+/// `ASPRError::InvalidId` describing the first violated invariant.
+pub fn validate_aspr_id(id: FIPSCode) -> Result<(), ASPRError> {
+    let Some(category) = SettingCategory::decode(id.category_code()) else {
+        return Err(ASPRError::InvalidId {
+            id,
+            reason: "the setting category is not a known setting category",
+        });
+    };
+    if category.max_id().is_some_and(|max_id| id.id() > max_id) {
+        return Err(ASPRError::InvalidId {
+            id,
+            reason: "the id exceeds the capacity of its setting category",
+        });
+    }
+    if category == SettingCategory::PrivateSchool && id.census_tract_code() != 0 {
+        return Err(ASPRError::InvalidId {
+            id,
+            reason: "a private school id must have census tract 0",
+        });
+    }
+    Ok(())
+}
+
+/// Whether `id` satisfies the invariants checked by `validate_aspr_id`, e.g. to filter out inconsistent ids.
+#[must_use]
+pub fn is_consistent(id: FIPSCode) -> bool {
+    validate_aspr_id(id).is_ok()
+}
+
+/// Projects `records` onto `(home_id, age)` pairs, skipping records without a home id.
+pub fn home_age_pairs(
+    records: impl IntoIterator<Item = ASPRPersonRecord>,
+) -> impl Iterator<Item = (FIPSCode, u8)> {
+    records
+        .into_iter()
+        .filter_map(|record| record.home_id.map(|home_id| (home_id, record.age)))
+}
+
+/// Pairs each of `records` with the full name of the state of its home id, e.g. `"Texas"`, for human-readable output. The
+/// name is `None` for a record without a home id or whose home id is in a state that is not one of the `USState`s.
+pub fn with_state_names(
+    records: impl IntoIterator<Item = ASPRPersonRecord>,
+) -> impl Iterator<Item = (ASPRPersonRecord, Option<&'static str>)> {
+    records.into_iter().map(|record| {
+        let name = record
+            .home_id
+            .and_then(|home_id| home_id.state().ok())
+            .map(|state| state.name());
+        (record, name)
+    })
+}
+
+/// Counts the records that occur more than once in `records`, that is, with the same age and the same three ids, which
+/// may indicate a bug in the data. (Distinct people sharing a home id are expected, since households share a home.)
+/// Records that occur only once are not included.
+pub fn find_duplicate_records(
+    records: impl IntoIterator<Item = ASPRPersonRecord>,
+) -> HashMap<ASPRPersonRecord, usize> {
+    let mut counts: HashMap<ASPRPersonRecord, usize> = HashMap::new();
+    for record in records {
+        *counts.entry(record).or_default() += 1;
+    }
+    counts.retain(|_, count| *count > 1);
+    counts
+}
+
+/// Returns the set of the states of the home ids of `records`, e.g. to find which states a data file covers. Records
+/// without a home id, or whose home id is in a state that is not one of the `USState`s, are skipped.
+pub fn home_states(records: impl IntoIterator<Item = ASPRPersonRecord>) -> StateSet {
+    StateSet::from_codes(records.into_iter().filter_map(|record| record.home_id))
+}
+
+/// The census tracts of a person's home (origin) and workplace (destination), as tract-level codes.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct OriginDestination {
+    pub home: FIPSCode,
+    pub work: FIPSCode,
+}
+
+/// Yields the home and workplace tracts of each of `records` that has both a home id and a work id, skipping the rest.
+pub fn od_pairs(
+    records: impl IntoIterator<Item = ASPRPersonRecord>,
+) -> impl Iterator<Item = OriginDestination> {
+    records.into_iter().filter_map(|record| {
+        Some(OriginDestination {
+            home: record.home_id?.parent_tract(),
+            work: record.work_id?.parent_tract(),
+        })
+    })
+}
+
+/// Returns the setting categories of the ids of `records` that occur in each state, e.g. to check whether a state file
+/// contains any private schools. All three ids of each record are counted, under the state of the id itself. Ids whose
+/// state or category code is not a valid `USState` or `SettingCategory` are skipped.
+pub fn category_presence(
+    records: impl IntoIterator<Item = ASPRPersonRecord>,
+) -> HashMap<USState, HashSet<SettingCategory>> {
+    let mut presence: HashMap<USState, HashSet<SettingCategory>> = HashMap::new();
+
+    for record in records {
+        let ids = [record.home_id, record.school_id, record.work_id];
+        for id in ids.into_iter().flatten() {
+            let category = SettingCategory::decode(id.category_code());
+            if let (Ok(state), Some(category)) = (id.state(), category) {
+                presence.entry(state).or_default().insert(category);
+            }
+        }
+    }
+
+    presence
+}
+
+/// Merges `iters`, each of which must yield its records sorted by home id, into a single stream of records sorted by home
+/// id, holding only one record per input in memory. Home ids are compared in hierarchical order (state, county, tract,
+/// and so on), and records without a home id sort first. Records with equal home ids are yielded in the order of their
+/// inputs in `iters`.
+///
+/// If an input is not sorted, the output is not sorted either, though every record is still yielded exactly once.
+pub fn merge_sorted_records<I: Iterator<Item = ASPRPersonRecord>>(
+    mut iters: Vec<I>,
+) -> impl Iterator<Item = ASPRPersonRecord> {
+    // The next record of each input, and a heap of the home ids of those records together with the index of their input
+    let mut heads: Vec<Option<ASPRPersonRecord>> = iters.iter_mut().map(Iterator::next).collect();
+    let mut heap: BinaryHeap<Reverse<(Option<FIPSCode>, usize)>> = heads
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, head)| head.map(|record| Reverse((record.home_id, idx))))
+        .collect();
+
+    std::iter::from_fn(move || {
+        let Reverse((_, idx)) = heap.pop()?;
+        let next = iters[idx].next();
+        if let Some(next) = next {
+            heap.push(Reverse((next.home_id, idx)));
+        }
+        std::mem::replace(&mut heads[idx], next)
+    })
+}
+
+/// An `ASPRPersonRecord` together with the original text of its id fields, so that ids that failed to parse (and are
+/// therefore `None` in `record`) can be inspected or corrected downstream. Produced by
+/// `ASPRRecordIterator::with_raw_fields` in the `archive` module.
+#[derive(Clone, Eq, PartialEq, Hash, Default, Debug)]
+pub struct ASPRPersonRecordRaw {
+    pub record: ASPRPersonRecord,
+    pub home_id: String,
+    pub school_id: String,
+    pub work_id: String,
+}
+
+/// An `ASPRPersonRecord` together with any text trailing each id in its field, e.g. `"|urban"` in the field
+/// `"110010109000024|urban"`, for exports that append per-id annotations. A trailing string is empty if its field holds
+/// only the id. Produced by `ASPRRecordIterator::with_annotations` in the `archive` module.
+#[derive(Clone, Eq, PartialEq, Hash, Default, Debug)]
+pub struct ASPRPersonRecordAnnotated {
+    pub record: ASPRPersonRecord,
+    pub home_rest: String,
+    pub school_rest: String,
+    pub work_rest: String,
+}
+
 /// A `SettingCategory` is not a FIPS code but is implicit in the ASPR synthetic population dataset
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Default, Debug)]
 #[repr(u8)]
@@ -92,6 +357,22 @@ impl SettingCategory {
     pub fn encode(self) -> u8 {
         self as u8
     }
+
+    /// The largest id the ASPR format can express for this category, or `None` if the category places no constraint on
+    /// the id. A census tract has no id of its own.
+    #[must_use]
+    pub fn max_id(self) -> Option<IdCode> {
+        match self {
+            SettingCategory::Unspecified => None,
+            SettingCategory::Home => Some(9_999),
+            // Four digits, but the parser only allots 11 bits.
+            SettingCategory::PrivateSchool => Some(2_047),
+            SettingCategory::PublicSchool => Some(999),
+            // Five digits, but the id field is 14 bits.
+            SettingCategory::Workplace => Some(16_383),
+            SettingCategory::CensusTract => Some(0),
+        }
+    }
 }
 
 impl From<SettingCategory> for u8 {
@@ -116,55 +397,126 @@ impl Display for SettingCategory {
 /// This formats the FIPS code as a string according to the ASPR format, which augments FIPS region codes with setting
 /// IDs. The category code and "data" field are not represented in this format. However, this function should round-trip
 /// for IDs from the ASPR synthetic population dataset.
-fn format_as_fips_code<W: Write>(f: &mut W, fips_code: FIPSCode) -> std::fmt::Result {
+///
+/// Codes with category `Unspecified` or `CensusTract` are formatted as plain GEOIDs at the most specific level
+/// present: 2 digits for a state, 5 for a county, and 11 for a census tract. Their id field is not represented.
+pub fn format_as_fips_code<W: Write>(f: &mut W, fips_code: FIPSCode) -> std::fmt::Result {
+    let Some(category) = SettingCategory::decode(fips_code.category_code()) else {
+        return Err(std::fmt::Error);
+    };
+
     write!(f, "{:02}", fips_code.state_code())?;
+
+    if let SettingCategory::Unspecified | SettingCategory::CensusTract = category {
+        // A plain GEOID, truncated after the most specific nonzero field
+        let tract = fips_code.census_tract_code();
+        if fips_code.county_code() != 0 || tract != 0 {
+            write!(f, "{:03}", fips_code.county_code())?;
+        }
+        if tract != 0 {
+            write!(f, "{:06}", tract)?;
+        }
+        return Ok(());
+    }
+
     write!(f, "{:03}", fips_code.county_code())?;
 
-    match SettingCategory::decode(fips_code.category_code()) {
-        Some(SettingCategory::Home) => {
+    match category {
+        SettingCategory::Home => {
             // 11-digit tract + 4-digit within-tract sequential id
             write!(f, "{:06}", fips_code.census_tract_code())?;
             write!(f, "{:04}", fips_code.id())
         }
 
-        Some(SettingCategory::Workplace) => {
+        SettingCategory::Workplace => {
             // 11-digit tract + 5-digit within-tract sequential id
             write!(f, "{:06}", fips_code.census_tract_code())?;
             write!(f, "{:05}", fips_code.id())
         }
 
-        Some(SettingCategory::PublicSchool) => {
+        SettingCategory::PublicSchool => {
             // 11-digit tract + 3-digit within-tract sequential id
             write!(f, "{:06}", fips_code.census_tract_code())?;
             write!(f, "{:03}", fips_code.id())
         }
 
-        Some(SettingCategory::PrivateSchool) => {
+        SettingCategory::PrivateSchool => {
             // 5-digit county + “xprvx” + 4-digit within-county sequential id
             write!(f, "xprvx")?;
             write!(f, "{:04}", fips_code.id())
         }
 
-        // ToDo: Give a reasonable representation for these categories.
-        Some(SettingCategory::Unspecified) 
-        | Some(SettingCategory::CensusTract)
-        | None => Err(std::fmt::Error),
+        // Handled above
+        SettingCategory::Unspecified | SettingCategory::CensusTract => unreachable!(),
     }
     // The category code and "data" field are not represented in this format.
     // write!(f, "{:01}", fips_code.category_code())?;
     // write!(f, "{:03}", fips_code.data())?;
 }
 
-fn format_as_fips_code_string(fips_code: FIPSCode) -> String {
+/// Returns the FIPS code formatted as a string according to the ASPR format, e.g. `"110010109000024"` for a home id
+/// or `"24031xprvx0150"` for a private school id. This is the inverse of the `parse_fips_*` functions in
+/// [`crate::parser`]. Returns an error if the setting category of the code has no representation in the ASPR format.
+pub fn to_geoid_string(fips_code: FIPSCode) -> Result<String, std::fmt::Error> {
     let mut buf = String::new();
-    format_as_fips_code(&mut buf, fips_code).unwrap();
-    buf
+    format_as_fips_code(&mut buf, fips_code)?;
+    Ok(buf)
+}
+
+/// Replaces the contents of `buf` with the FIPS code formatted as by `to_geoid_string`. Reusing one buffer across calls
+/// avoids allocating a new `String` for each code, e.g. when logging in a hot loop. Returns an error, leaving `buf`
+/// empty, if the setting category of the code has no representation in the ASPR format.
+pub fn write_geoid(fips_code: FIPSCode, buf: &mut String) -> std::fmt::Result {
+    buf.clear();
+    format_as_fips_code(buf, fips_code).inspect_err(|_| buf.clear())
+}
+
+/// Returns the length of the string `to_geoid_string` produces for `fips_code`, computed without formatting. This is
+/// 2, 5, or 11 for a plain state, county, or census tract GEOID; 14 for a school id; 15 for a home id; and 16 for a
+/// workplace id. Returns `None` if the setting category of the code has no representation in the ASPR format.
+#[must_use]
+pub fn geoid_len(fips_code: FIPSCode) -> Option<usize> {
+    let length = match SettingCategory::decode(fips_code.category_code())? {
+        SettingCategory::Unspecified | SettingCategory::CensusTract => {
+            if fips_code.census_tract_code() != 0 {
+                11
+            } else if fips_code.county_code() != 0 {
+                5
+            } else {
+                2
+            }
+        }
+        SettingCategory::Home => 15,
+        SettingCategory::Workplace => 16,
+        SettingCategory::PublicSchool | SettingCategory::PrivateSchool => 14,
+    };
+    Some(length)
+}
+
+/// Returns a human-readable description of `id` as by `FIPSCode::describe`, but with the setting category described by
+/// its `SettingCategory` name, e.g. `"Texas, County 201, Tract 2231 (Home #24)"` rather than `"(Setting 1 #24)"`. A
+/// category code that is not a `SettingCategory` is described by its numeric code, as by `FIPSCode::describe`.
+#[must_use]
+pub fn describe_id(id: FIPSCode) -> String {
+    let mut description = id.parent_tract().describe();
+    if id.category_code() != 0 || id.id() != 0 {
+        let category = match SettingCategory::decode(id.category_code()) {
+            Some(category) => category.to_string(),
+            None => format!("Setting {}", id.category_code()),
+        };
+        // Writing to a `String` is infallible.
+        write!(description, " ({} #{})", category, id.id()).unwrap();
+    }
+    description
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser::{parse_fips_home_id, parse_fips_school_id, parse_fips_workplace_id};
+    use crate::parser::{
+        parse_fips_home_id, parse_fips_id, parse_fips_school_id, parse_fips_workplace_id,
+    };
+    use fips::USState;
 
     #[test]
     fn text_round_trip_formatting() {
@@ -178,18 +530,476 @@ mod tests {
         let (_, parsed_public_school_id) = parse_fips_school_id(public_school_id).unwrap();
         let (_, parsed_private_school_id) = parse_fips_school_id(private_school_id).unwrap();
 
-        assert_eq!(home_id, format_as_fips_code_string(parsed_home_id));
-        assert_eq!(
-            workplace_id,
-            format_as_fips_code_string(parsed_workplace_id)
-        );
+        assert_eq!(home_id, to_geoid_string(parsed_home_id).unwrap());
+        assert_eq!(workplace_id, to_geoid_string(parsed_workplace_id).unwrap());
         assert_eq!(
             public_school_id,
-            format_as_fips_code_string(parsed_public_school_id)
+            to_geoid_string(parsed_public_school_id).unwrap()
         );
         assert_eq!(
             private_school_id,
-            format_as_fips_code_string(parsed_private_school_id)
+            to_geoid_string(parsed_private_school_id).unwrap()
+        );
+    }
+
+    #[test]
+    fn private_school_round_trip() {
+        let private_school_id = "24031xprvx0150";
+        let (_, parsed) = parse_fips_school_id(private_school_id).unwrap();
+
+        assert_eq!(
+            parsed.category_code(),
+            SettingCategory::PrivateSchool.encode()
+        );
+        assert_eq!(parsed.census_tract_code(), 0);
+        assert_eq!(parsed.id(), 150);
+        assert_eq!(to_geoid_string(parsed).unwrap(), private_school_id);
+
+        // The id is zero-padded to 4 digits after the marker.
+        let small_id = parsed.set_id(7).unwrap();
+        assert_eq!(to_geoid_string(small_id).unwrap(), "24031xprvx0007");
+
+        // Codes without an ASPR representation are an error.
+        assert!(to_geoid_string(parsed.set_category(15).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_write_geoid() {
+        let ids = [
+            "110010109000024",
+            "24031xprvx0150",
+            "1100100620201546",
+            "11001010900012",
+        ];
+        let mut buf = String::with_capacity(16);
+        let capacity = buf.capacity();
+        for id in ids {
+            let (_, parsed) = parse_fips_id(id).unwrap();
+            write_geoid(parsed, &mut buf).unwrap();
+            assert_eq!(buf, id);
+            assert_eq!(buf, to_geoid_string(parsed).unwrap());
+            // The buffer is reused rather than reallocated.
+            assert_eq!(buf.capacity(), capacity);
+        }
+
+        let (_, parsed) = parse_fips_id(ids[0]).unwrap();
+        assert!(write_geoid(parsed.set_category(15).unwrap(), &mut buf).is_err());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_home_age_pairs() {
+        let (_, home_id) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, work_id) = parse_fips_workplace_id("1100100620201546").unwrap();
+        let records = vec![
+            ASPRPersonRecord {
+                age: 41,
+                home_id: Some(home_id),
+                school_id: None,
+                work_id: Some(work_id),
+            },
+            ASPRPersonRecord {
+                age: 52,
+                home_id: None,
+                school_id: None,
+                work_id: Some(work_id),
+            },
+            ASPRPersonRecord {
+                age: 8,
+                home_id: Some(home_id),
+                ..Default::default()
+            },
+        ];
+
+        let pairs: Vec<(FIPSCode, u8)> = home_age_pairs(records).collect();
+        assert_eq!(pairs, vec![(home_id, 41), (home_id, 8)]);
+    }
+
+    #[test]
+    fn test_with_state_names() {
+        let home = |id: &str| Some(parse_fips_home_id(id).unwrap().1);
+        let records = vec![
+            ASPRPersonRecord {
+                age: 41,
+                home_id: home("482012231000024"),
+                ..Default::default()
+            },
+            ASPRPersonRecord {
+                age: 52,
+                ..Default::default()
+            },
+            ASPRPersonRecord {
+                age: 7,
+                home_id: home("110010109000024"),
+                ..Default::default()
+            },
+            ASPRPersonRecord {
+                age: 30,
+                home_id: home("720010001000001"),
+                ..Default::default()
+            },
+        ];
+
+        let named: Vec<(u8, Option<&str>)> = with_state_names(records)
+            .map(|(record, name)| (record.age, name))
+            .collect();
+        assert_eq!(
+            named,
+            vec![
+                (41, Some("Texas")),
+                (52, None),
+                (7, Some("District of Columbia")),
+                (30, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_duplicate_records() {
+        let (_, home_id) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, work_id) = parse_fips_workplace_id("1100100620201546").unwrap();
+        let parent = ASPRPersonRecord {
+            age: 41,
+            home_id: Some(home_id),
+            school_id: None,
+            work_id: Some(work_id),
+        };
+        let child = ASPRPersonRecord {
+            age: 7,
+            home_id: Some(home_id),
+            ..Default::default()
+        };
+        let older = ASPRPersonRecord { age: 42, ..parent };
+        let records = [parent, child, parent, older, parent];
+
+        let duplicates = find_duplicate_records(records);
+        assert_eq!(duplicates, HashMap::from([(parent, 3)]));
+
+        assert!(find_duplicate_records([parent, child]).is_empty());
+    }
+
+    #[test]
+    fn test_home_states() {
+        let home = |id: &str| Some(parse_fips_home_id(id).unwrap().1);
+        let records = [
+            ASPRPersonRecord {
+                age: 41,
+                home_id: home("560010001000001"),
+                ..Default::default()
+            },
+            ASPRPersonRecord {
+                age: 7,
+                home_id: home("110010109000024"),
+                ..Default::default()
+            },
+            ASPRPersonRecord {
+                age: 52,
+                ..Default::default()
+            },
+            ASPRPersonRecord {
+                age: 8,
+                home_id: home("560010001000002"),
+                ..Default::default()
+            },
+        ];
+        let states: Vec<USState> = home_states(records).iter().collect();
+        assert_eq!(states, vec![USState::DC, USState::WY]);
+    }
+
+    #[test]
+    fn test_merge_sorted_records() {
+        let home = |text: &str| parse_fips_home_id(text).unwrap().1;
+        let record = |age: u8, home_id: Option<FIPSCode>| ASPRPersonRecord {
+            age,
+            home_id,
+            ..Default::default()
+        };
+        // DC sorts before MD, and within DC tract 010900 before 010910.
+        let first = vec![
+            record(1, None),
+            record(2, Some(home("110010109000024"))),
+            record(3, Some(home("240310001000010"))),
+        ];
+        let second = vec![
+            record(4, Some(home("110010109000024"))),
+            record(5, Some(home("110010109100001"))),
+            record(6, Some(home("240310001000011"))),
+        ];
+
+        let merged: Vec<u8> = merge_sorted_records(vec![first.into_iter(), second.into_iter()])
+            .map(|record| record.age)
+            .collect();
+        assert_eq!(merged, vec![1, 2, 4, 5, 3, 6]);
+
+        let empty: Vec<std::vec::IntoIter<ASPRPersonRecord>> = vec![];
+        assert_eq!(merge_sorted_records(empty).count(), 0);
+    }
+
+    #[test]
+    fn test_validate_private_school_tract() {
+        let (_, home_id) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, private_school) = parse_fips_school_id("24031xprvx0150").unwrap();
+        let mut record = ASPRPersonRecord {
+            age: 8,
+            home_id: Some(home_id),
+            school_id: Some(private_school),
+            work_id: None,
+        };
+        assert!(validate_aspr_id(private_school).is_ok());
+        assert!(record.validate().is_ok());
+
+        let with_tract = private_school.set_tract(10900).unwrap();
+        record.school_id = Some(with_tract);
+        assert!(matches!(
+            validate_aspr_id(with_tract),
+            Err(ASPRError::InvalidId { id, .. }) if id == with_tract
+        ));
+        assert!(matches!(record.validate(), Err(ASPRError::InvalidId { .. })));
+
+        // Only private school ids are scoped to a county.
+        assert!(validate_aspr_id(home_id).is_ok());
+    }
+
+    #[test]
+    fn test_expected_categories() {
+        assert_eq!(
+            ASPRPersonRecord::expected_categories(RecordSlot::Home),
+            &[SettingCategory::Home]
+        );
+        assert_eq!(
+            ASPRPersonRecord::expected_categories(RecordSlot::School),
+            &[
+                SettingCategory::PublicSchool,
+                SettingCategory::PrivateSchool
+            ]
+        );
+        assert_eq!(
+            ASPRPersonRecord::expected_categories(RecordSlot::Work),
+            &[SettingCategory::Workplace]
+        );
+
+        let (_, home_id) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, work_id) = parse_fips_workplace_id("1100100620201546").unwrap();
+        let (_, private_school) = parse_fips_school_id("24031xprvx0150").unwrap();
+        let record = ASPRPersonRecord {
+            age: 8,
+            home_id: Some(home_id),
+            school_id: Some(private_school),
+            work_id: None,
+        };
+        assert!(record.validate().is_ok());
+        for slot in RecordSlot::ALL {
+            if let Some(id) = record.id(slot) {
+                let category = SettingCategory::decode(id.category_code()).unwrap();
+                assert!(ASPRPersonRecord::expected_categories(slot).contains(&category));
+            }
+        }
+
+        // Ids in the wrong slots
+        let swapped = ASPRPersonRecord {
+            home_id: Some(work_id),
+            work_id: Some(home_id),
+            ..record
+        };
+        assert!(matches!(
+            swapped.validate(),
+            Err(ASPRError::InvalidId { id, .. }) if id == work_id
+        ));
+    }
+
+    #[test]
+    fn test_same_person() {
+        let (_, home_id) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, work_id) = parse_fips_workplace_id("1100100620201546").unwrap();
+        let record = ASPRPersonRecord {
+            age: 34,
+            home_id: Some(home_id),
+            school_id: None,
+            work_id: Some(work_id),
+        };
+        let other_version = ASPRPersonRecord {
+            work_id: Some(work_id.set_id(1547).unwrap()),
+            ..record
+        };
+        assert!(record.same_person(&other_version));
+        assert!(!record.same_person(&ASPRPersonRecord { age: 35, ..record }));
+        assert!(!record.same_person(&ASPRPersonRecord {
+            home_id: Some(home_id.set_id(25).unwrap()),
+            ..record
+        }));
+
+        let homeless = ASPRPersonRecord {
+            age: 34,
+            ..Default::default()
+        };
+        assert!(!homeless.same_person(&homeless));
+    }
+
+    #[test]
+    fn test_is_consistent() {
+        let (_, home_id) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, public_school) = parse_fips_school_id("11001010900012").unwrap();
+        let (_, private_school) = parse_fips_school_id("24031xprvx0150").unwrap();
+        let (_, work_id) = parse_fips_workplace_id("1100100620201546").unwrap();
+        for id in [home_id, public_school, private_school, work_id] {
+            assert!(is_consistent(id), "{id:?}");
+        }
+
+        // Ids that fit in the 14-bit field but not in the digits of their category.
+        assert!(!is_consistent(home_id.set_id(10_000).unwrap()));
+        assert!(!is_consistent(public_school.set_id(1_000).unwrap()));
+        assert!(is_consistent(public_school.set_id(999).unwrap()));
+        // A private school with a census tract.
+        assert!(!is_consistent(private_school.set_tract(10900).unwrap()));
+        // A census tract with an id, and an unknown category.
+        let tract = home_id
+            .set_id(0)
+            .unwrap()
+            .set_category(SettingCategory::CensusTract.into())
+            .unwrap();
+        assert!(is_consistent(tract));
+        assert!(!is_consistent(tract.set_id(1).unwrap()));
+        assert!(!is_consistent(home_id.set_category(9).unwrap()));
+        // An unspecified category places no constraint on the id.
+        let unspecified = home_id.set_category(0).unwrap();
+        assert!(is_consistent(unspecified.set_id(16_000).unwrap()));
+    }
+
+    #[test]
+    fn test_category_presence() {
+        let (_, dc_home) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, dc_work) = parse_fips_workplace_id("1100100620201546").unwrap();
+        let (_, dc_public_school) = parse_fips_school_id("11001009810157").unwrap();
+        let (_, md_home) = parse_fips_home_id("240310001000010").unwrap();
+        let (_, md_private_school) = parse_fips_school_id("24031xprvx0085").unwrap();
+        let records = vec![
+            ASPRPersonRecord {
+                age: 41,
+                home_id: Some(dc_home),
+                school_id: None,
+                work_id: Some(dc_work),
+            },
+            ASPRPersonRecord {
+                age: 8,
+                home_id: Some(dc_home),
+                school_id: Some(dc_public_school),
+                work_id: None,
+            },
+            ASPRPersonRecord {
+                age: 12,
+                home_id: Some(md_home),
+                school_id: Some(md_private_school),
+                work_id: None,
+            },
+        ];
+
+        let presence = category_presence(records);
+        assert_eq!(presence.len(), 2);
+        assert_eq!(
+            presence[&USState::DC],
+            HashSet::from([
+                SettingCategory::Home,
+                SettingCategory::Workplace,
+                SettingCategory::PublicSchool
+            ])
+        );
+        assert_eq!(
+            presence[&USState::MD],
+            HashSet::from([SettingCategory::Home, SettingCategory::PrivateSchool])
+        );
+    }
+
+    #[test]
+    fn test_od_pairs() {
+        let (_, home_id) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, work_id) = parse_fips_workplace_id("1100100620201546").unwrap();
+        let (_, other_work_id) = parse_fips_workplace_id("1100100620200012").unwrap();
+        let records = vec![
+            ASPRPersonRecord {
+                age: 41,
+                home_id: Some(home_id),
+                school_id: None,
+                work_id: Some(work_id),
+            },
+            ASPRPersonRecord {
+                age: 8,
+                home_id: Some(home_id),
+                ..Default::default()
+            },
+            ASPRPersonRecord {
+                age: 52,
+                home_id: None,
+                school_id: None,
+                work_id: Some(work_id),
+            },
+            ASPRPersonRecord {
+                age: 39,
+                home_id: Some(home_id),
+                school_id: None,
+                work_id: Some(other_work_id),
+            },
+        ];
+
+        let pairs: Vec<OriginDestination> = od_pairs(records).collect();
+        let expected = OriginDestination {
+            home: FIPSCode::with_tract(USState::DC.into(), 1, 10900).unwrap(),
+            work: FIPSCode::with_tract(USState::DC.into(), 1, 6202).unwrap(),
+        };
+        // Workplaces in the same tract give the same pair.
+        assert_eq!(pairs, vec![expected, expected]);
+    }
+
+    #[test]
+    fn test_geoid_len() {
+        let state = FIPSCode::with_state(USState::TX);
+        let county = FIPSCode::with_county(USState::TX.into(), 201).unwrap();
+        let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let census_tract = tract
+            .set_category(SettingCategory::CensusTract.into())
+            .unwrap();
+        let (_, home) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, workplace) = parse_fips_workplace_id("1100100620201546").unwrap();
+        let (_, public_school) = parse_fips_school_id("11001009810157").unwrap();
+        let (_, private_school) = parse_fips_school_id("24031xprvx0150").unwrap();
+
+        let expected = [
+            (state, 2),
+            (county, 5),
+            (tract, 11),
+            (census_tract, 11),
+            (public_school, 14),
+            (private_school, 14),
+            (home, 15),
+            (workplace, 16),
+        ];
+        for (fips_code, length) in expected {
+            assert_eq!(geoid_len(fips_code), Some(length));
+            assert_eq!(to_geoid_string(fips_code).unwrap().len(), length);
+        }
+        assert_eq!(to_geoid_string(tract).unwrap(), "48201223100");
+
+        let invalid = tract.set_category(15).unwrap();
+        assert_eq!(geoid_len(invalid), None);
+    }
+
+    #[test]
+    fn test_describe_id() {
+        let (_, home_id) = parse_fips_home_id("482012231000024").unwrap();
+        assert_eq!(
+            describe_id(home_id),
+            "Texas, County 201, Tract 2231 (Home #24)"
+        );
+        let (_, private_school_id) = parse_fips_school_id("24031xprvx0150").unwrap();
+        assert_eq!(
+            describe_id(private_school_id),
+            "Maryland, County 031 (Private School #150)"
+        );
+        // Codes without a category or id are described as by `FIPSCode::describe`.
+        assert_eq!(
+            describe_id(home_id.parent_tract()),
+            home_id.parent_tract().describe()
         );
+        let unknown = home_id.set_category(15).unwrap();
+        assert_eq!(unknown.describe(), describe_id(unknown));
     }
 }
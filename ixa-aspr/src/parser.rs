@@ -1,16 +1,25 @@
 //! These high-level functions parse the concatenated FIPS code and ids.
+//!
+//! The parsers of the geographic fragments of an id, and the result and error types shared by all parsers, are those of
+//! the core crate, re-exported here so that a single `use` of this module suffices.
 
 use ixa_fips::StateCode;
 use crate::{
     SettingCategory,
     FIPSCode,
-    fips::parser::{parse_decimal_digits_to_bits, parse_state_code, FIPSParseResult, FIPSParserError},
+    fips::parser::parse_decimal_digits_to_bits,
     fips::{CountyCode, IdCode, TractCode},
 };
+pub use crate::fips::{
+    FIPSParseResult, FIPSParserError, parse_county_code, parse_state_code, parse_tract_code,
+};
 
-/// Parses the input as a FIPS code for a home id. Returns `(FIPSCode, rest)`,
-/// where `rest` is the remaining input after the FIPS code.
+/// Parses the input as a FIPS code for a home id. Unlike the parsers of the fragments of an id, this parser requires
+/// the whole input to be the id: it returns `FIPSParserError::InvalidLength` if `input` is not exactly 15 bytes long,
+/// so the remainder it returns is always empty.
 pub fn parse_fips_home_id(input: &str) -> FIPSParseResult<FIPSCode> {
+    check_not_empty(input)?;
+    check_length(input, 15).map_err(|error| (input, error))?;
     let (rest, state): (&str, StateCode) = parse_state_code(input)?;
     let (rest, county): (&str, CountyCode) = parse_county_code(rest)?;
     let (rest, tract): (&str, TractCode) = parse_tract_code(rest)?;
@@ -27,13 +36,16 @@ pub fn parse_fips_home_id(input: &str) -> FIPSParseResult<FIPSCode> {
     }
 }
 
-/// Parses the input as a FIPS code for a school id. Returns `(FIPSCode, rest)`,
-/// where `rest` is the remaining input after the FIPS code.
+/// Parses the input as a FIPS code for a school id. Like `parse_fips_home_id`, this parser requires the whole input to
+/// be the id: it returns `FIPSParserError::InvalidLength` if `input` is not exactly 14 bytes long, so the remainder it
+/// returns is always empty. Public and private school ids are both 14 bytes long.
 pub fn parse_fips_school_id(input: &str) -> FIPSParseResult<FIPSCode> {
+    check_not_empty(input)?;
+    check_length(input, 14).map_err(|error| (input, error))?;
     let (rest, state): (&str, StateCode) = parse_state_code(input)?;
     let (rest, county): (&str, CountyCode) = parse_county_code(rest)?;
 
-    if rest.starts_with("x") {
+    if strip_private_school_marker(rest).is_some() {
         // Private school id
         let (rest, school_id): (&str, IdCode) = parse_private_school_id(rest)?;
         let fips_code = FIPSCode::new(
@@ -72,9 +84,12 @@ pub fn parse_fips_school_id(input: &str) -> FIPSParseResult<FIPSCode> {
     }
 }
 
-/// Parses the input as a FIPS code for a workplace id. Returns `(FIPSCode, rest)`,
-/// where `rest` is the remaining input after the FIPS code.
+/// Parses the input as a FIPS code for a workplace id. Like `parse_fips_home_id`, this parser requires the whole input
+/// to be the id: it returns `FIPSParserError::InvalidLength` if `input` is not exactly 16 bytes long, so the remainder
+/// it returns is always empty.
 pub fn parse_fips_workplace_id(input: &str) -> FIPSParseResult<FIPSCode> {
+    check_not_empty(input)?;
+    check_length(input, 16).map_err(|error| (input, error))?;
     let (rest, state): (&str, StateCode) = parse_state_code(input)?;
     let (rest, county): (&str, CountyCode) = parse_county_code(rest)?;
     let (rest, tract): (&str, TractCode) = parse_tract_code(rest)?;
@@ -96,18 +111,214 @@ pub fn parse_fips_workplace_id(input: &str) -> FIPSParseResult<FIPSCode> {
     }
 }
 
-/// Parses the first three digits of `input` as a county
-/// code. Enforces the requirement that the value is representable using 10
-/// bits (which is tautologically always true).
-pub fn parse_county_code(input: &str) -> FIPSParseResult<CountyCode> {
-    parse_decimal_digits_to_bits(3, 10, input).map(|(rest, value)| (rest, value as CountyCode))
+/// The number of digits of each field of an id following the 2-digit state code, for parsing ids of datasets that
+/// derive from the ASPR format but use other widths. A width of 0 omits the field, which is then 0.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct FieldWidths {
+    pub county: u32,
+    pub tract: u32,
+    pub id: u32,
 }
 
-/// Parses the first six digits of `input` as a tract
-/// code. Enforces the requirement that the value is representable using 20
-/// bits (which is tautologically always true).
-pub fn parse_tract_code(input: &str) -> FIPSParseResult<TractCode> {
-    parse_decimal_digits_to_bits(6, 20, input).map(|(rest, value)| (rest, value as TractCode))
+impl FieldWidths {
+    /// The widths of a home id.
+    pub const HOME: Self = Self {
+        county: 3,
+        tract: 6,
+        id: 4,
+    };
+    /// The widths of a public school id.
+    pub const PUBLIC_SCHOOL: Self = Self {
+        county: 3,
+        tract: 6,
+        id: 3,
+    };
+    /// The widths of a workplace id.
+    pub const WORKPLACE: Self = Self {
+        county: 3,
+        tract: 6,
+        id: 5,
+    };
+
+    /// The length of an id with these widths, including the state code.
+    #[must_use]
+    pub const fn id_length(&self) -> u32 {
+        2 + self.county + self.tract + self.id
+    }
+}
+
+/// Parses `input` as an id whose fields have the digit counts given by `widths`, generalizing `parse_fips_home_id` and
+/// `parse_fips_workplace_id`. Like them, it requires the whole input to be the id, returning
+/// `FIPSParserError::InvalidLength` if `input` is not exactly `widths.id_length()` bytes long, and
+/// `FIPSParserError::ValueExceedsCapacity` if a field does not fit in its bits in `FIPSCode`. The widths do not determine
+/// a setting category, so the category of the result is `SettingCategory::Unspecified`; set it with
+/// `FIPSCode::set_category` if needed.
+pub fn parse_custom(input: &str, widths: FieldWidths) -> FIPSParseResult<FIPSCode> {
+    check_not_empty(input)?;
+    check_length(input, widths.id_length()).map_err(|error| (input, error))?;
+    let (rest, state): (&str, StateCode) = parse_state_code(input)?;
+    let (rest, county) = parse_decimal_digits_to_bits(widths.county, 10, rest)?;
+    let (rest, tract) = parse_decimal_digits_to_bits(widths.tract, 20, rest)?;
+    let (rest, id) = parse_decimal_digits_to_bits(widths.id, 14, rest)?;
+
+    // The values fit in the bits of their fields, so the conversions and the construction are infallible.
+    let bug = "FIPS code is invalid. This is a bug in the ASPR parser.";
+    let fips_code = FIPSCode::new(
+        state,
+        CountyCode::try_from(county).expect(bug),
+        TractCode::try_from(tract).expect(bug),
+        SettingCategory::Unspecified.into(),
+        IdCode::try_from(id).expect(bug),
+        0,
+    )
+    .expect(bug);
+    Ok((rest, fips_code))
+}
+
+/// The lengths of ids recognized by `detect_id_category`, in increasing order.
+const ID_LENGTHS: [u32; 5] = [5, 11, 14, 15, 16];
+
+/// Infers the category of an id from its length (and, for 14-character ids, the `"xprvx"` marker of a private school
+/// id) according to the following table:
+///
+/// | **Length** | **Category**                                                                  |
+/// |-----------:|:----------------------------------------------------------------------------- |
+/// |          5 | Ambiguous: a county GEOID, a ZCTA, or a state legislative district GEOID      |
+/// |         11 | `CensusTract`: state + county + tract                                         |
+/// |         14 | `PrivateSchool` if characters 6–10 are `"xprvx"`, otherwise `PublicSchool`    |
+/// |         15 | `Home`: tract + 4-digit id                                                    |
+/// |         16 | `Workplace`: tract + 5-digit id                                               |
+///
+/// A 15-digit census block GEOID has the same length as a home id, but blocks cannot be represented by a `FIPSCode`,
+/// so 15 characters are taken to be a home id. Returns `FIPSParserError::Ambiguous` for a length that fits more than
+/// one kind of code, and `FIPSParserError::InvalidLength` for any other length, with `expected` the next recognized
+/// length (or the longest, if `input` is longer than every recognized length). The characters are not otherwise
+/// checked.
+pub fn detect_id_category(input: &str) -> Result<SettingCategory, FIPSParserError> {
+    let found = u32::try_from(input.len()).unwrap_or(u32::MAX);
+    let has_private_marker = input
+        .get(5..)
+        .and_then(strip_private_school_marker)
+        .is_some();
+    match found {
+        5 => Err(FIPSParserError::Ambiguous { found }),
+        11 => Ok(SettingCategory::CensusTract),
+        14 if has_private_marker => Ok(SettingCategory::PrivateSchool),
+        14 => Ok(SettingCategory::PublicSchool),
+        15 => Ok(SettingCategory::Home),
+        16 => Ok(SettingCategory::Workplace),
+        _ => {
+            let expected = ID_LENGTHS
+                .into_iter()
+                .find(|&length| length > found)
+                .unwrap_or(ID_LENGTHS[ID_LENGTHS.len() - 1]);
+            Err(FIPSParserError::InvalidLength { expected, found })
+        }
+    }
+}
+
+/// Parses the input as an id of the category inferred by `detect_id_category`. Returns `(FIPSCode, rest)`, where
+/// `rest` is the remaining input after the FIPS code. A census tract GEOID is given the category `CensusTract`.
+pub fn parse_fips_id(input: &str) -> FIPSParseResult<FIPSCode> {
+    check_not_empty(input)?;
+    match detect_id_category(input).map_err(|error| (input, error))? {
+        SettingCategory::Home => parse_fips_home_id(input),
+        SettingCategory::PublicSchool | SettingCategory::PrivateSchool => {
+            parse_fips_school_id(input)
+        }
+        SettingCategory::Workplace => parse_fips_workplace_id(input),
+        SettingCategory::CensusTract | SettingCategory::Unspecified => {
+            let (rest, state): (&str, StateCode) = parse_state_code(input)?;
+            let (rest, county): (&str, CountyCode) = parse_county_code(rest)?;
+            let (rest, tract): (&str, TractCode) = parse_tract_code(rest)?;
+            let fips_code =
+                FIPSCode::with_category(state, county, tract, SettingCategory::CensusTract.into())
+                    .expect("FIPS code is invalid. This is a bug in the ASPR parser.");
+            Ok((rest, fips_code))
+        }
+    }
+}
+
+/// The raw substrings of the fields of an ASPR id, as returned by `split_aspr_id_parts`. The substrings are not
+/// checked to be digits.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct AsprIdParts<'a> {
+    pub state: &'a str,
+    pub county: &'a str,
+    /// Absent for private school ids, which have no census tract.
+    pub tract: Option<&'a str>,
+    /// The within-tract (or, for private schools, within-county) sequential id, without the `"xprvx"` marker of a
+    /// private school id. Empty for a plain census tract GEOID.
+    pub id: &'a str,
+}
+
+/// Splits `input` into the raw substrings of its fields according to the ASPR id layout of `category`, e.g.
+/// `"482012231000024"` as a home id into state `"48"`, county `"201"`, tract `"223100"`, and id `"0024"`. Ids of
+/// category `Unspecified` or `CensusTract` are split as 11-digit census tract GEOIDs. This is a view of the input for
+/// diagnosing malformed ids, not a parse: only the length of the input, and for private school ids the `"xprvx"`
+/// marker, are checked.
+pub fn split_aspr_id_parts(
+    input: &str,
+    category: SettingCategory,
+) -> Result<AsprIdParts<'_>, FIPSParserError> {
+    if let Some(found) = input.chars().find(|c| !c.is_ascii()) {
+        return Err(FIPSParserError::InvalidDigit { found });
+    }
+
+    // The width of the id field, which follows the 11-digit tract GEOID except for private schools
+    let id_width = match category {
+        SettingCategory::Unspecified | SettingCategory::CensusTract => 0,
+        SettingCategory::Home => 4,
+        SettingCategory::Workplace => 5,
+        SettingCategory::PublicSchool => 3,
+        SettingCategory::PrivateSchool => {
+            // 5-digit county + “xprvx” + 4-digit within-county sequential id
+            check_length(input, 14)?;
+            if let Some(found) = input[5..10]
+                .chars()
+                .zip(PRIVATE_SCHOOL_MARKER.chars())
+                .find_map(|(found, expected)| {
+                    (!found.eq_ignore_ascii_case(&expected)).then_some(found)
+                })
+            {
+                return Err(FIPSParserError::InvalidDigit { found });
+            }
+            return Ok(AsprIdParts {
+                state: &input[..2],
+                county: &input[2..5],
+                tract: None,
+                id: &input[10..],
+            });
+        }
+    };
+
+    check_length(input, 11 + id_width)?;
+    Ok(AsprIdParts {
+        state: &input[..2],
+        county: &input[2..5],
+        tract: Some(&input[5..11]),
+        id: &input[11..],
+    })
+}
+
+/// Returns `FIPSParserError::InvalidLength` unless `input` is exactly `expected` bytes long.
+fn check_length(input: &str, expected: u32) -> Result<(), FIPSParserError> {
+    let found = u32::try_from(input.len()).unwrap_or(u32::MAX);
+    if found == expected {
+        Ok(())
+    } else {
+        Err(FIPSParserError::InvalidLength { expected, found })
+    }
+}
+
+/// Returns `FIPSParserError::Empty` if `input` is empty or consists only of whitespace. This distinguishes a
+/// legitimately absent id field from a malformed one.
+fn check_not_empty(input: &str) -> Result<(), (&str, FIPSParserError)> {
+    if input.trim().is_empty() {
+        Err((input, FIPSParserError::Empty))
+    } else {
+        Ok(())
+    }
 }
 
 /// Parses the first four digits of `input` as a (monotonically increasing) id
@@ -117,11 +328,24 @@ pub fn parse_home_id(input: &str) -> FIPSParseResult<IdCode> {
     parse_decimal_digits_to_bits(4, 14, input).map(|(rest, value)| (rest, value as IdCode))
 }
 
+/// The marker separating the county from the id in a private school id, matched case-insensitively.
+const PRIVATE_SCHOOL_MARKER: &str = "xprvx";
+
+/// Returns the rest of `input` after a leading private school marker (`"xprvx"` in any case), or `None` if `input` does
+/// not start with the marker.
+fn strip_private_school_marker(input: &str) -> Option<&str> {
+    let marker_len = PRIVATE_SCHOOL_MARKER.len();
+    input
+        .get(..marker_len)
+        .filter(|prefix| prefix.eq_ignore_ascii_case(PRIVATE_SCHOOL_MARKER))
+        .map(|_| &input[marker_len..])
+}
+
 /// Parses the first four digits of `input` as a (monotonically increasing)
-/// id number after stripping `"xprvx"`, if it exists. Enforces the
+/// id number after stripping `"xprvx"` (in any case), if it exists. Enforces the
 /// requirement that the value is representable using 11 bits.
 pub fn parse_private_school_id(input: &str) -> FIPSParseResult<IdCode> {
-    let input = input.strip_prefix("xprvx").unwrap_or(input);
+    let input = strip_private_school_marker(input).unwrap_or(input);
     parse_decimal_digits_to_bits(4, 11, input).map(|(rest, value)| (rest, value as IdCode))
 }
 
@@ -140,8 +364,9 @@ pub fn parse_workplace_id(input: &str) -> FIPSParseResult<IdCode> {
 }
 
 /// Parses the next sequence of decimal digits in `input` without respect to
-/// its length or how many bits are required to represent it (thought it must
-/// implicitly be at most 64).
+/// its length or how many bits are required to represent it (though it must
+/// implicitly be at most 64). Returns `FIPSParserError::Overflow` if the
+/// value of the digits does not fit in a `u64`.
 pub fn parse_integer(input: &str) -> FIPSParseResult<u64> {
     // Find the first non-digit character
     let digit_end = input
@@ -161,42 +386,36 @@ pub fn parse_integer(input: &str) -> FIPSParseResult<u64> {
 
     // Parse the digit substring
     let value = match input[..digit_end].parse::<u64>() {
-        Ok(v) => v,
-        Err(parse_int_error) => match parse_int_error.kind() {
-            std::num::IntErrorKind::Empty => {
-                return Err((
-                    input,
-                    FIPSParserError::InvalidLength {
-                        expected: 1,
-                        found: 0,
-                    },
-                ));
-            }
-
-            std::num::IntErrorKind::InvalidDigit => {
-                return Err((
-                    input,
-                    FIPSParserError::InvalidDigit {
-                        found: input.chars().next().unwrap(),
-                    },
-                ));
-            }
-
-            _ => {
-                return Err((
-                    input,
-                    FIPSParserError::ValueExceedsCapacity {
-                        value: u64::MAX,
-                        capacity: u64::MAX,
-                    },
-                ));
-            }
-        },
+        Ok(value) => value,
+        Err(parse_int_error) => {
+            // The substring is a nonempty run of ASCII digits, so it can only fail to parse by overflowing.
+            debug_assert_eq!(*parse_int_error.kind(), std::num::IntErrorKind::PosOverflow);
+            let found = u32::try_from(digit_end).unwrap_or(u32::MAX);
+            return Err((input, FIPSParserError::Overflow { found }));
+        }
     };
 
     Ok((&input[digit_end..], value))
 }
 
+/// Like `parse_integer`, but returns `FIPSParserError::InvalidLength` if the run of decimal digits at the start of
+/// `input` is longer than `max_digits`, so that a run too long to be a field, e.g. several ids concatenated in corrupt
+/// data, is rejected rather than parsed into a huge number.
+pub fn parse_integer_bounded(input: &str, max_digits: u32) -> FIPSParseResult<u64> {
+    let digit_count = input.bytes().take_while(u8::is_ascii_digit).count();
+    let found = u32::try_from(digit_count).unwrap_or(u32::MAX);
+    if found > max_digits {
+        return Err((
+            input,
+            FIPSParserError::InvalidLength {
+                expected: max_digits,
+                found,
+            },
+        ));
+    }
+    parse_integer(input)
+}
+
 #[cfg(test)]
 mod tests {
     use ixa_fips::{ExpandedFIPSCode, StateCode, USState};
@@ -248,6 +467,26 @@ mod tests {
         assert!(parse_private_school_id("xprvx2048").is_err()); // Exceeds 11 bits
     }
 
+    #[test]
+    fn test_private_school_marker_case() {
+        // The marker is matched in any case
+        assert_eq!(parse_private_school_id("XPRVX0150"), Ok(("", 150)));
+        assert_eq!(parse_private_school_id("xPrVx0150"), Ok(("", 150)));
+        assert_eq!(
+            parse_fips_school_id("24031XPRVX0150"),
+            parse_fips_school_id("24031xprvx0150")
+        );
+        assert_eq!(
+            detect_id_category("24031XPRVX0150"),
+            Ok(SettingCategory::PrivateSchool)
+        );
+
+        // A leading `x` that does not begin the marker is not consumed
+        assert!(parse_private_school_id("x1234").is_err());
+        assert!(parse_private_school_id("xprv1234").is_err());
+        assert!(parse_fips_school_id("24031x12345678").is_err());
+    }
+
     #[test]
     fn test_parse_public_school_id() {
         // Basic successful parsing
@@ -290,7 +529,7 @@ mod tests {
     #[test]
     fn test_fips_home_id() {
         let home_id = "110010109000024";
-        let state_code: StateCode = 11;
+        let state_code = StateCode::new(11).unwrap();
         let county_code: CountyCode = 1;
         let tract_code: TractCode = 10900;
         let home_id_code = 24;
@@ -306,7 +545,7 @@ mod tests {
     #[test]
     fn test_fips_work_id() {
         let workplace_id = "1100100620201546";
-        let state_code: StateCode = 11;
+        let state_code = StateCode::new(11).unwrap();
         let county_code: CountyCode = 1;
         let tract_code: TractCode = 6202;
         let workplace_id_code = 1546;
@@ -319,10 +558,62 @@ mod tests {
         assert_eq!(parsed_workplace_id.id(), workplace_id_code);
     }
 
+    #[test]
+    fn test_parse_custom() {
+        let (_, home_id) = parse_fips_home_id("110010109000024").unwrap();
+        let (_, parsed) = parse_custom("110010109000024", FieldWidths::HOME).unwrap();
+        assert_eq!(parsed, home_id.set_category(0).unwrap());
+
+        // A 6-digit id
+        let widths = FieldWidths {
+            id: 6,
+            ..FieldWidths::HOME
+        };
+        assert_eq!(widths.id_length(), 17);
+        let (rest, parsed) = parse_custom("48201223100012345", widths).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(parsed.state_code(), StateCode::new(48).unwrap());
+        assert_eq!(parsed.county_code(), 201);
+        assert_eq!(parsed.census_tract_code(), 223_100);
+        assert_eq!(parsed.id(), 12_345);
+
+        // The id must still fit in 14 bits.
+        assert_eq!(
+            parse_custom("48201223100016384", widths),
+            Err((
+                "016384",
+                FIPSParserError::ValueExceedsCapacity {
+                    value: 16_384,
+                    capacity: 16_383,
+                }
+            ))
+        );
+        assert_eq!(
+            parse_custom("4820122310001234", widths),
+            Err((
+                "4820122310001234",
+                FIPSParserError::InvalidLength {
+                    expected: 17,
+                    found: 16,
+                }
+            ))
+        );
+
+        // A width of 0 omits the field.
+        let county_scoped = FieldWidths {
+            county: 3,
+            tract: 0,
+            id: 4,
+        };
+        let (_, parsed) = parse_custom("240310150", county_scoped).unwrap();
+        assert_eq!(parsed.census_tract_code(), 0);
+        assert_eq!(parsed.id(), 150);
+    }
+
     #[test]
     fn test_fips_public_school_id() {
         let public_school_id = "11001009810157";
-        let state_code: StateCode = 11;
+        let state_code = StateCode::new(11).unwrap();
         let county_code: CountyCode = 1;
         let tract_code: TractCode = 9810;
         let public_school_id_code = 157;
@@ -338,7 +629,7 @@ mod tests {
     #[test]
     fn test_fips_private_school_id() {
         let private_school_id = "24031xprvx0150";
-        let state_code: StateCode = 24;
+        let state_code = StateCode::new(24).unwrap();
         let county_code: CountyCode = 31;
         let tract_code: TractCode = 0;
         let private_school_id_code = 150;
@@ -351,6 +642,159 @@ mod tests {
         assert_eq!(parsed_private_school_id.id(), private_school_id_code);
     }
 
+    #[test]
+    fn test_empty_vs_garbage_id() {
+        // Absent ids
+        assert_eq!(parse_fips_home_id(""), Err(("", FIPSParserError::Empty)));
+        assert_eq!(parse_fips_school_id("  "), Err(("  ", FIPSParserError::Empty)));
+        assert_eq!(parse_fips_workplace_id(""), Err(("", FIPSParserError::Empty)));
+
+        // Malformed ids
+        assert_eq!(
+            parse_fips_workplace_id("garbagegarbage!!"),
+            Err(("garbagegarbage!!", FIPSParserError::InvalidDigit { found: 'g' }))
+        );
+        assert!(matches!(
+            parse_fips_home_id("4820"),
+            Err((_, FIPSParserError::InvalidLength { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_fips_id_wrong_length() {
+        // Home id missing its leading zero
+        assert_eq!(
+            parse_fips_home_id("21300003000173"),
+            Err((
+                "21300003000173",
+                FIPSParserError::InvalidLength {
+                    expected: 15,
+                    found: 14
+                }
+            ))
+        );
+        // Workplace id with an extra trailing digit
+        assert_eq!(
+            parse_fips_workplace_id("11001006202015460"),
+            Err((
+                "11001006202015460",
+                FIPSParserError::InvalidLength {
+                    expected: 16,
+                    found: 17
+                }
+            ))
+        );
+        assert!(matches!(
+            parse_fips_school_id("24031xprvx015"),
+            Err((_, FIPSParserError::InvalidLength { expected: 14, found: 13 }))
+        ));
+    }
+
+    #[test]
+    fn test_detect_id_category() {
+        assert_eq!(detect_id_category("48201223100"), Ok(SettingCategory::CensusTract));
+        assert_eq!(detect_id_category("11001009810157"), Ok(SettingCategory::PublicSchool));
+        assert_eq!(detect_id_category("24031xprvx0085"), Ok(SettingCategory::PrivateSchool));
+        assert_eq!(detect_id_category("110010109000024"), Ok(SettingCategory::Home));
+        assert_eq!(detect_id_category("1100100620201546"), Ok(SettingCategory::Workplace));
+
+        // A county GEOID and a ZCTA have the same length.
+        assert_eq!(
+            detect_id_category("20746"),
+            Err(FIPSParserError::Ambiguous { found: 5 })
+        );
+        assert_eq!(
+            detect_id_category("4820192975"),
+            Err(FIPSParserError::InvalidLength {
+                expected: 11,
+                found: 10
+            })
+        );
+        assert_eq!(
+            detect_id_category("11001006202015460"),
+            Err(FIPSParserError::InvalidLength {
+                expected: 16,
+                found: 17
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_fips_id() {
+        for (input, category) in [
+            ("110010109000024", SettingCategory::Home),
+            ("1100100620201546", SettingCategory::Workplace),
+            ("11001009810157", SettingCategory::PublicSchool),
+            ("24031xprvx0150", SettingCategory::PrivateSchool),
+        ] {
+            let (rest, fips_code) = parse_fips_id(input).unwrap();
+            assert_eq!(rest, "");
+            assert_eq!(fips_code.category_code(), category.encode());
+        }
+
+        let (_, tract) = parse_fips_id("48201223100").unwrap();
+        assert_eq!(
+            tract,
+            FIPSCode::with_category(USState::TX.into(), 201, 223_100, SettingCategory::CensusTract.into()).unwrap()
+        );
+
+        assert_eq!(parse_fips_id(""), Err(("", FIPSParserError::Empty)));
+        assert_eq!(
+            parse_fips_id("20746"),
+            Err(("20746", FIPSParserError::Ambiguous { found: 5 }))
+        );
+    }
+
+    #[test]
+    fn test_split_aspr_id_parts() {
+        let parts = split_aspr_id_parts("482012231000024", SettingCategory::Home).unwrap();
+        assert_eq!(
+            parts,
+            AsprIdParts {
+                state: "48",
+                county: "201",
+                tract: Some("223100"),
+                id: "0024",
+            }
+        );
+
+        let parts = split_aspr_id_parts("1100100620201546", SettingCategory::Workplace).unwrap();
+        assert_eq!((parts.tract, parts.id), (Some("006202"), "01546"));
+
+        let parts = split_aspr_id_parts("11001009810157", SettingCategory::PublicSchool).unwrap();
+        assert_eq!((parts.tract, parts.id), (Some("009810"), "157"));
+
+        let parts = split_aspr_id_parts("24031xprvx0085", SettingCategory::PrivateSchool).unwrap();
+        assert_eq!(
+            parts,
+            AsprIdParts {
+                state: "24",
+                county: "031",
+                tract: None,
+                id: "0085",
+            }
+        );
+
+        let parts = split_aspr_id_parts("48201223100", SettingCategory::CensusTract).unwrap();
+        assert_eq!((parts.tract, parts.id), (Some("223100"), ""));
+
+        // Malformed digits are not rejected, so they can be located.
+        let parts = split_aspr_id_parts("11001006202O1546", SettingCategory::Workplace).unwrap();
+        assert_eq!(parts.id, "O1546");
+
+        assert_eq!(
+            split_aspr_id_parts("48201223100002", SettingCategory::Home),
+            Err(FIPSParserError::InvalidLength {
+                expected: 15,
+                found: 14
+            })
+        );
+        assert_eq!(
+            split_aspr_id_parts("24031xpubx0085", SettingCategory::PrivateSchool),
+            Err(FIPSParserError::InvalidDigit { found: 'u' })
+        );
+    }
+
     #[test]
     fn test_parse_integer() {
         // Basic successful parsing
@@ -370,6 +814,50 @@ mod tests {
         // Error cases
         assert!(parse_integer("").is_err()); // Empty string
         assert!(parse_integer("abc").is_err()); // No digits
+        assert_eq!(
+            parse_integer("18446744073709551616end"),
+            Err((
+                "18446744073709551616end",
+                FIPSParserError::Overflow { found: 20 }
+            ))
+        ); // u64 max + 1
+
+        // The error locates the overflowing digits
+        let input = "123456789012345678901234567890,rest";
+        let (rest, error) = parse_integer(input).unwrap_err();
+        assert_eq!(rest, input);
+        assert_eq!(error, FIPSParserError::Overflow { found: 30 });
+        assert_eq!(
+            error.to_string(),
+            "Value of 30 digits overflows a 64-bit integer"
+        );
+    }
+
+    #[test]
+    fn test_parse_integer_bounded() {
+        assert_eq!(
+            parse_integer_bounded("4820122310001234,", 16),
+            Ok((",", 4_820_122_310_001_234))
+        );
+        assert_eq!(parse_integer_bounded("7", 1), Ok(("", 7)));
+
+        let input = "4820122310001234482012231";
+        assert_eq!(
+            parse_integer_bounded(input, 16),
+            Err((
+                input,
+                FIPSParserError::InvalidLength {
+                    expected: 16,
+                    found: 25
+                }
+            ))
+        );
+        // Without the bound, the run is parsed and overflows.
+        assert!(matches!(
+            parse_integer(input),
+            Err((_, FIPSParserError::Overflow { found: 25 }))
+        ));
+        assert!(parse_integer_bounded("abc", 16).is_err());
     }
 
     // Additional combined tests
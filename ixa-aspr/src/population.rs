@@ -0,0 +1,171 @@
+//! An in-memory index of ASPR person records grouped by household.
+
+use crate::ASPRPersonRecord;
+use ixa_fips::FIPSCode;
+use std::{
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+};
+
+/// Groups `ASPRPersonRecord`s by their full home id, so that every member of a household can be looked up at once.
+/// Build one by collecting an iterator of records:
+///
+/// ```ignore
+/// # use ixa_aspr::{archive::ASPRRecordIterator, population::PopulationIndex};
+/// # use ixa_fips::USState;
+/// let index: PopulationIndex = ASPRRecordIterator::state_population(USState::AK).unwrap().collect();
+/// ```
+///
+/// Records without a home id are not indexed.
+#[derive(Clone, Default, Debug)]
+pub struct PopulationIndex {
+    households: HashMap<FIPSCode, Vec<ASPRPersonRecord>>,
+    record_count: usize,
+}
+
+impl PopulationIndex {
+    /// Returns the members of the household with the given home id, or `None` if there is no such household.
+    #[must_use]
+    pub fn household(&self, home_id: FIPSCode) -> Option<&[ASPRPersonRecord]> {
+        self.households.get(&home_id).map(Vec::as_slice)
+    }
+
+    /// Returns the distinct census tracts containing a household, as tract-level codes in hierarchical order.
+    #[must_use]
+    pub fn tracts(&self) -> BTreeSet<FIPSCode> {
+        self.households.keys().map(FIPSCode::parent_tract).collect()
+    }
+
+    /// Returns the number of households in the index.
+    #[must_use]
+    pub fn household_count(&self) -> usize {
+        self.households.len()
+    }
+
+    /// Returns the number of records in the index.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.record_count
+    }
+
+    /// Returns `true` if the index contains no records.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.record_count == 0
+    }
+
+    /// Adds `record` to the household of its home id. Records without a home id are ignored.
+    pub fn insert(&mut self, record: ASPRPersonRecord) {
+        if let Some(home_id) = record.home_id {
+            self.households.entry(home_id).or_default().push(record);
+            self.record_count += 1;
+        }
+    }
+}
+
+impl FromIterator<ASPRPersonRecord> for PopulationIndex {
+    fn from_iter<T: IntoIterator<Item = ASPRPersonRecord>>(iter: T) -> Self {
+        let mut index = PopulationIndex::default();
+        index.extend(iter);
+        index
+    }
+}
+
+impl Extend<ASPRPersonRecord> for PopulationIndex {
+    fn extend<T: IntoIterator<Item = ASPRPersonRecord>>(&mut self, iter: T) {
+        for record in iter {
+            self.insert(record);
+        }
+    }
+}
+
+/// Yields every unordered pair of members of each household of `records`, each pair once, e.g. as the contacts for
+/// within-household transmission. Records are grouped into households by full home id as in `PopulationIndex`, so
+/// records without a home id are skipped. The households are visited in increasing order of home id, and the pairs of a
+/// household in the order of its members in `records`, so the pairs come out in the same order on every run.
+///
+/// A household of `n` members yields `n * (n - 1) / 2` pairs, so the number of pairs grows quadratically with household
+/// size: a group quarters "household" of 1,000 residents, such as a dormitory, alone yields 499,500 pairs. Filter out
+/// large households first if that is not wanted.
+pub fn household_contact_pairs(
+    records: impl IntoIterator<Item = ASPRPersonRecord>,
+) -> impl Iterator<Item = (ASPRPersonRecord, ASPRPersonRecord)> {
+    let index: PopulationIndex = records.into_iter().collect();
+    let mut households: Vec<(FIPSCode, Vec<ASPRPersonRecord>)> =
+        index.households.into_iter().collect();
+    households.sort_unstable_by_key(|(home_id, _)| *home_id);
+
+    households.into_iter().flat_map(|(_, members)| {
+        // Shared rather than copied, so each member's pairs index into the one list of the household's members.
+        let members: Rc<[ASPRPersonRecord]> = members.into();
+        (0..members.len()).flat_map(move |first| {
+            let members = Rc::clone(&members);
+            (first + 1..members.len()).map(move |second| (members[first], members[second]))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_fips_home_id;
+
+    fn home(id: &str) -> FIPSCode {
+        parse_fips_home_id(id).unwrap().1
+    }
+
+    fn person(age: u8, home_id: Option<&str>) -> ASPRPersonRecord {
+        ASPRPersonRecord {
+            age,
+            home_id: home_id.map(home),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_collect_population_index() {
+        let records = vec![
+            person(41, Some("110010109000001")),
+            person(39, Some("110010109000001")),
+            person(7, Some("110010109000001")),
+            person(66, Some("110010109000002")),
+            person(25, Some("110010109100001")),
+            person(30, None),
+        ];
+
+        let index: PopulationIndex = records.into_iter().collect();
+        assert_eq!(index.len(), 5);
+        assert_eq!(index.household_count(), 3);
+
+        let household = index.household(home("110010109000001")).unwrap();
+        let ages: Vec<u8> = household.iter().map(|record| record.age).collect();
+        assert_eq!(ages, vec![41, 39, 7]);
+        assert!(index.household(home("110010109000003")).is_none());
+
+        let tracts: Vec<u32> = index
+            .tracts()
+            .iter()
+            .map(FIPSCode::census_tract_code)
+            .collect();
+        assert_eq!(tracts, vec![10900, 10910]);
+    }
+
+    #[test]
+    fn test_household_contact_pairs() {
+        let records = vec![
+            person(66, Some("110010109000002")),
+            person(41, Some("110010109000001")),
+            person(64, Some("110010109000002")),
+            person(39, Some("110010109000001")),
+            person(30, None),
+            person(7, Some("110010109000001")),
+            person(50, Some("110010109000003")),
+        ];
+
+        // Households in increasing order of home id, whatever the order of their records
+        let pairs: Vec<(u8, u8)> = household_contact_pairs(records)
+            .map(|(first, second)| (first.age, second.age))
+            .collect();
+        assert_eq!(pairs, vec![(41, 39), (41, 7), (39, 7), (66, 64)]);
+    }
+}
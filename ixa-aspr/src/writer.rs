@@ -0,0 +1,191 @@
+/*!
+
+Writing `ASPRPersonRecord`s as CSV rows in the format of the ASPR synthetic population dataset: a header row
+`age,homeId,schoolId,workplaceId` followed by one row per person, each id formatted by `to_geoid_string` and absent ids
+left empty. Files written this way can be read back with the record iterators of the `archive` module.
+
+The `partition_by_state` function splits a stream of records into one such file per state, e.g. to recover per-state
+files from a combined file.
+
+*/
+
+use crate::{errors::ASPRError, to_geoid_string, ASPRPersonRecord};
+use ixa_fips::{FIPSCode, USState, VALID_STATE_CODES};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs::File,
+    io::{BufWriter, ErrorKind, Write},
+    path::Path,
+};
+
+/// The header row of a data file, without the line break.
+pub const ASPR_HEADER: &str = "age,homeId,schoolId,workplaceId";
+
+/// Writes the header row of a data file to `writer`.
+pub fn write_header<W: Write>(writer: &mut W) -> Result<(), ASPRError> {
+    writeln!(writer, "{}", ASPR_HEADER).map_err(ASPRError::Io)
+}
+
+/// Writes `record` to `writer` as a data row. Returns `ASPRError::InvalidId` if an id of the record has a setting
+/// category with no representation in the ASPR format.
+pub fn write_record<W: Write>(writer: &mut W, record: &ASPRPersonRecord) -> Result<(), ASPRError> {
+    let home_id = format_id(record.home_id)?;
+    let school_id = format_id(record.school_id)?;
+    let work_id = format_id(record.work_id)?;
+    writeln!(
+        writer,
+        "{},{},{},{}",
+        record.age, home_id, school_id, work_id
+    )
+    .map_err(ASPRError::Io)
+}
+
+/// Formats `id` as in the data files, with an absent id formatted as the empty string.
+fn format_id(id: Option<FIPSCode>) -> Result<String, ASPRError> {
+    let Some(id) = id else {
+        return Ok(String::new());
+    };
+    to_geoid_string(id).map_err(|_| ASPRError::InvalidId {
+        id,
+        reason: "the setting category has no representation in the ASPR format",
+    })
+}
+
+/// Writes each of `records` to a data file in `out_dir` for the state of its home id, named as in the all-states
+/// directory of the dataset, e.g. `tx.csv`. Records without a home id, or whose home id is in a state that is not one of
+/// the `USState`s, are written to the file `fallback_file_name` in `out_dir` instead. Each file is created, replacing
+/// any existing file, when the first record for it arrives, so no file is created for a state without records.
+///
+/// Returns an `ASPRError::Io` of kind `InvalidInput`, before any file is created, if `fallback_file_name` is the name of
+/// a state file, compared case-insensitively, as the two files would overwrite each other.
+pub fn partition_by_state(
+    records: impl IntoIterator<Item = ASPRPersonRecord>,
+    out_dir: impl AsRef<Path>,
+    fallback_file_name: &str,
+) -> Result<(), ASPRError> {
+    let out_dir = out_dir.as_ref();
+    let clashes = VALID_STATE_CODES
+        .iter()
+        .filter_map(|&code| USState::decode(code).ok())
+        .any(|state| state_file_name(state).eq_ignore_ascii_case(fallback_file_name));
+    if clashes {
+        return Err(ASPRError::Io(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("fallback file name {fallback_file_name} is the name of a state file"),
+        )));
+    }
+    let create = |file_name: &str| -> Result<BufWriter<File>, ASPRError> {
+        let file = File::create(out_dir.join(file_name)).map_err(ASPRError::Io)?;
+        let mut writer = BufWriter::new(file);
+        write_header(&mut writer)?;
+        Ok(writer)
+    };
+
+    let mut state_writers: HashMap<USState, BufWriter<File>> = HashMap::new();
+    let mut fallback_writer: Option<BufWriter<File>> = None;
+
+    for record in records {
+        let state = record.home_id.and_then(|home_id| home_id.state().ok());
+        let writer = match state {
+            Some(state) => match state_writers.entry(state) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(create(&state_file_name(state))?),
+            },
+            None => match &mut fallback_writer {
+                Some(writer) => writer,
+                None => fallback_writer.insert(create(fallback_file_name)?),
+            },
+        };
+        write_record(writer, &record)?;
+    }
+
+    // Dropping a `BufWriter` discards any error flushing it, so flush explicitly.
+    for writer in state_writers.values_mut().chain(fallback_writer.as_mut()) {
+        writer.flush().map_err(ASPRError::Io)?;
+    }
+    Ok(())
+}
+
+/// The name of the data file of `state` in the all-states directory of the dataset, e.g. `tx.csv`.
+fn state_file_name(state: USState) -> String {
+    format!("{}.csv", state.as_ref().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_fips_home_id, parse_fips_workplace_id};
+
+    #[test]
+    fn test_write_record() {
+        let record = ASPRPersonRecord {
+            age: 34,
+            home_id: Some(parse_fips_home_id("110010109000024").unwrap().1),
+            school_id: None,
+            work_id: Some(parse_fips_workplace_id("1100101090000123").unwrap().1),
+        };
+        let mut buffer = Vec::new();
+        write_header(&mut buffer).unwrap();
+        write_record(&mut buffer, &record).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "age,homeId,schoolId,workplaceId\n34,110010109000024,,1100101090000123\n"
+        );
+    }
+
+    #[test]
+    fn test_partition_by_state() {
+        let out_dir =
+            std::env::temp_dir().join(format!("ixa_aspr_partition_{}", std::process::id()));
+        std::fs::create_dir_all(&out_dir).unwrap();
+
+        let home = |id: &str| Some(parse_fips_home_id(id).unwrap().1);
+        let records = [
+            ASPRPersonRecord {
+                age: 7,
+                home_id: home("482012231000024"),
+                ..Default::default()
+            },
+            ASPRPersonRecord {
+                age: 40,
+                home_id: home("560010001000001"),
+                ..Default::default()
+            },
+            ASPRPersonRecord {
+                age: 8,
+                home_id: home("482012231000025"),
+                ..Default::default()
+            },
+            ASPRPersonRecord {
+                age: 61,
+                ..Default::default()
+            },
+        ];
+        partition_by_state(records, &out_dir, "no_home.csv").unwrap();
+
+        let read = |file_name: &str| std::fs::read_to_string(out_dir.join(file_name)).unwrap();
+        assert_eq!(
+            read("tx.csv"),
+            "age,homeId,schoolId,workplaceId\n7,482012231000024,,\n8,482012231000025,,\n"
+        );
+        assert_eq!(
+            read("wy.csv"),
+            "age,homeId,schoolId,workplaceId\n40,560010001000001,,\n"
+        );
+        assert_eq!(
+            read("no_home.csv"),
+            "age,homeId,schoolId,workplaceId\n61,,,\n"
+        );
+        assert!(!out_dir.join("ak.csv").exists());
+
+        // A fallback file named like a state file is rejected rather than clobbering it.
+        let result = partition_by_state([], &out_dir, "TX.csv");
+        assert!(matches!(result, Err(ASPRError::Io(e)) if e.kind() == ErrorKind::InvalidInput));
+        assert_eq!(
+            read("tx.csv"),
+            "age,homeId,schoolId,workplaceId\n7,482012231000024,,\n8,482012231000025,,\n"
+        );
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+}
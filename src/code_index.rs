@@ -0,0 +1,152 @@
+//! A compact on-disk format for a sorted set of `FIPSCode`s, and a reader supporting fast membership tests.
+//!
+//! # File Format
+//!
+//! An index file is a big-endian `u64` count `n` followed by the raw encodings (see `FIPSCode::as_u64`) of `n` codes as
+//! big-endian `u64`s in increasing order. Because numerical order coincides with hierarchical order, the codes of a
+//! region are contiguous in the file.
+//!
+//! With the `memmap2` feature, `CodeIndex::open` memory-maps the file, so that only the pages touched by a search are
+//! read. Otherwise the whole file is read into memory.
+
+use crate::FIPSCode;
+use std::{
+    fs::File,
+    io::{BufWriter, Error, ErrorKind, Write},
+    path::Path,
+};
+
+/// The size in bytes of the count and of each code in an index file.
+const WORD_SIZE: usize = size_of::<u64>();
+
+/// Writes `sorted_codes` to a new index file at `path`, replacing any existing file. The codes must be sorted in
+/// increasing order (e.g. with `slice::sort`) for `CodeIndex::contains` to find them.
+pub fn write_code_index(path: impl AsRef<Path>, sorted_codes: &[FIPSCode]) -> std::io::Result<()> {
+    debug_assert!(
+        sorted_codes.is_sorted(),
+        "the codes of a `CodeIndex` must be sorted"
+    );
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    writer.write_all(&(sorted_codes.len() as u64).to_be_bytes())?;
+    for code in sorted_codes {
+        writer.write_all(&code.as_u64().to_be_bytes())?;
+    }
+
+    writer.flush()
+}
+
+#[cfg(feature = "memmap2")]
+type Backing = memmap2::Mmap;
+#[cfg(not(feature = "memmap2"))]
+type Backing = Vec<u8>;
+
+/// A sorted set of codes read from an index file written by `write_code_index`.
+pub struct CodeIndex {
+    data: Backing,
+    len: usize,
+}
+
+impl CodeIndex {
+    /// Opens the index file at `path`. Returns an error of kind `ErrorKind::InvalidData` if the size of the file does
+    /// not agree with its count.
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        #[cfg(feature = "memmap2")]
+        // Safety: The map is read-only. As with any memory map, the file must not be modified while it is open.
+        let data = unsafe { memmap2::Mmap::map(&File::open(path)?)? };
+        #[cfg(not(feature = "memmap2"))]
+        let data = std::fs::read(path)?;
+
+        let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+        let count = data
+            .get(..WORD_SIZE)
+            .ok_or_else(|| invalid("code index file is missing its count"))?;
+        let len = usize::try_from(u64::from_be_bytes(count.try_into().unwrap()))
+            .map_err(|_| invalid("code index count is too large"))?;
+        if Some(data.len())
+            != len
+                .checked_mul(WORD_SIZE)
+                .and_then(|size| size.checked_add(WORD_SIZE))
+        {
+            return Err(invalid("code index file size does not match its count"));
+        }
+
+        Ok(Self { data, len })
+    }
+
+    /// Returns the number of codes in the index.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the raw encoding of the code at `index`, which must be less than `self.len()`.
+    fn get(&self, index: usize) -> u64 {
+        let start = WORD_SIZE * (index + 1);
+        u64::from_be_bytes(self.data[start..start + WORD_SIZE].try_into().unwrap())
+    }
+
+    /// Returns `true` if the index contains `code`, by binary search. The data region is significant.
+    #[must_use]
+    pub fn contains(&self, code: FIPSCode) -> bool {
+        let target = code.as_u64();
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.get(mid).cmp(&target) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return true,
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::USState;
+
+    #[test]
+    fn test_code_index_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("ixa_fips_code_index_{}.bin", std::process::id()));
+        let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let mut codes: Vec<FIPSCode> = tract.id_range(50).unwrap().step_by(2).collect();
+        codes.push(FIPSCode::with_county(USState::AK.into(), 130).unwrap());
+        codes.sort();
+
+        write_code_index(&path, &codes).unwrap();
+        let index = CodeIndex::open(&path).unwrap();
+
+        assert_eq!(index.len(), 26);
+        for code in &codes {
+            assert!(index.contains(*code));
+        }
+        assert!(!index.contains(tract.set_id(2).unwrap()));
+        assert!(!index.contains(tract));
+        assert!(!index.contains(codes[0].set_data(1).unwrap()));
+        assert!(!index.contains(FIPSCode::with_state(USState::WY)));
+
+        // A truncated file is rejected.
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        assert_eq!(
+            CodeIndex::open(&path).err().unwrap().kind(),
+            ErrorKind::InvalidData
+        );
+
+        write_code_index(&path, &[]).unwrap();
+        let index = CodeIndex::open(&path).unwrap();
+        assert!(index.is_empty());
+        assert!(!index.contains(tract));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,322 @@
+//! Utilities for working with collections and streams of `FIPSCode`s.
+
+use crate::FIPSCode;
+use std::collections::{BTreeSet, HashMap};
+#[cfg(feature = "fast_hash")]
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// Collapses each run of consecutive equal codes into a single code together with the length of the run, like
+/// `uniq -c`. Codes are compared with the usual equality operators, so the data region is significant. If `codes` is
+/// sorted, each distinct code is yielded exactly once, which makes this a cheap aggregation primitive that does not
+/// require a `HashMap`.
+pub fn dedup_consecutive(
+    codes: impl Iterator<Item = FIPSCode>,
+) -> impl Iterator<Item = (FIPSCode, usize)> {
+    let mut codes = codes.peekable();
+
+    std::iter::from_fn(move || {
+        let code = codes.next()?;
+        let mut count = 1;
+        while codes.next_if_eq(&code).is_some() {
+            count += 1;
+        }
+        Some((code, count))
+    })
+}
+
+/// Removes consecutive codes that are equal under `FIPSCode::compare_non_data`, that is, that differ at most in the data
+/// region, keeping the first of each run. This is the in-place counterpart of `dedup_consecutive`; if `codes` is sorted,
+/// every location is left exactly once.
+pub fn dedup_non_data(codes: &mut Vec<FIPSCode>) {
+    codes.dedup_by(|next, kept| next.compare_non_data(*kept).is_eq());
+}
+
+/// Sorts `codes` in hierarchical order: by state, then county, tract, category, and ID number, as by
+/// `FIPSCode::compare_non_data`. The sort is stable, so codes that differ only in the data region keep their relative
+/// order. Because the fields are encoded from the most significant bits down in this order, this is also the numerical
+/// order of `FIPSCode::as_u64` with the data region masked out.
+pub fn sort_codes(codes: &mut [FIPSCode]) {
+    codes.sort_by(|a, b| a.compare_non_data(*b));
+}
+
+/// Returns the codes of `b` that are not in `a` (added) and the codes of `a` that are not in `b` (removed), e.g. to
+/// compare two versions of a dataset. Unlike the set operations of `BTreeSet`, which use the bit-exact `Ord`, codes are
+/// compared as by `FIPSCode::compare_non_data`: a code whose location occurs in both sets, even with different data, is
+/// neither added nor removed. Both lists are in increasing order.
+#[must_use]
+pub fn fips_set_diff(
+    a: &BTreeSet<FIPSCode>,
+    b: &BTreeSet<FIPSCode>,
+) -> (Vec<FIPSCode>, Vec<FIPSCode>) {
+    let without_data = |code: &FIPSCode| code.without_data();
+    let a_locations: BTreeSet<FIPSCode> = a.iter().map(without_data).collect();
+    let b_locations: BTreeSet<FIPSCode> = b.iter().map(without_data).collect();
+
+    let added = b
+        .iter()
+        .filter(|code| !a_locations.contains(&without_data(code)))
+        .copied()
+        .collect();
+    let removed = a
+        .iter()
+        .filter(|code| !b_locations.contains(&without_data(code)))
+        .copied()
+        .collect();
+    (added, removed)
+}
+
+/// Assigns dense sequential `u32` indices to `FIPSCode`s in the order they are first seen, e.g. for indexing into the
+/// per-region arrays of a simulation, and maps indices back to codes.
+#[derive(Clone, Debug, Default)]
+pub struct FIPSInterner {
+    indices: HashMap<FIPSCode, u32>,
+    codes: Vec<FIPSCode>,
+}
+
+impl FIPSInterner {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of `code`, assigning it the next unused index if `code` has not been seen before.
+    pub fn intern(&mut self, code: FIPSCode) -> u32 {
+        *self.indices.entry(code).or_insert_with(|| {
+            let index = u32::try_from(self.codes.len())
+                .expect("more than `u32::MAX` distinct codes interned");
+            self.codes.push(code);
+            index
+        })
+    }
+
+    /// Returns the code assigned `index`, or `None` if no code has been assigned it.
+    #[must_use]
+    pub fn resolve(&self, index: u32) -> Option<FIPSCode> {
+        self.codes.get(index as usize).copied()
+    }
+
+    /// Returns the number of distinct codes interned.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+}
+
+/// A `HashMap` keyed by `FIPSCode` that uses `FIPSHasher` in place of the default hasher. Because a `FIPSCode` is a
+/// single `u64`, the default hasher's resistance to adversarial keys is wasted on it when grouping millions of records.
+#[cfg(feature = "fast_hash")]
+pub type FIPSMap<V> = HashMap<FIPSCode, V, BuildHasherDefault<FIPSHasher>>;
+
+/// A hasher for `FIPSCode`s that hashes the encoded `u64` with a multiply, then xors the product with itself shifted
+/// right by 32 bits. The encoded value is not used as the hash directly: the hash table picks buckets from the least
+/// significant bits of the hash, and the low fields of a `FIPSCode` (data, id, and for tract-level codes also the
+/// category) are usually zero, so every tract would land in the same bucket.
+#[cfg(feature = "fast_hash")]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct FIPSHasher(u64);
+
+#[cfg(feature = "fast_hash")]
+impl Hasher for FIPSHasher {
+    #[inline(always)]
+    fn finish(&self) -> u64 {
+        // Fibonacci hashing: the multiply carries every bit upward, and the xor with the shifted product folds the high
+        // bits back down into the low bits.
+        let product = self.0.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        product ^ (product >> 32)
+    }
+
+    #[inline(always)]
+    fn write_u64(&mut self, value: u64) {
+        self.0 = value;
+    }
+
+    /// `FIPSCode` only ever calls `write_u64`; this fallback keeps the hasher correct for other keys.
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(byte);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StateCode, USState};
+
+    #[test]
+    fn test_dedup_consecutive() {
+        let a = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let b = FIPSCode::with_tract(USState::TX.into(), 201, 223_200).unwrap();
+        let c = FIPSCode::with_county(USState::WY.into(), 1).unwrap();
+        let codes = vec![a, a, a, b, c, c, a];
+
+        let runs: Vec<(FIPSCode, usize)> = dedup_consecutive(codes.into_iter()).collect();
+        assert_eq!(runs, vec![(a, 3), (b, 1), (c, 2), (a, 1)]);
+
+        assert_eq!(dedup_consecutive(std::iter::empty()).count(), 0);
+    }
+
+    /// A minimal deterministic pseudorandom number generator (`SplitMix64`) for property tests.
+    struct SplitMix64(u64);
+
+    impl SplitMix64 {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        /// Returns a value in `0..=max`, or, half of the time, in `0..=3` so that codes often share their upper fields.
+        fn field(&mut self, max: u64) -> u64 {
+            let bound = if self.next() & 1 == 0 { 3 } else { max };
+            self.next() % (bound + 1)
+        }
+
+        fn code(&mut self) -> FIPSCode {
+            let state = 1 + self.field(126) as u8;
+            FIPSCode::new(
+                StateCode::new(state).unwrap(),
+                self.field(1023) as u16,
+                self.field(1_048_575) as u32,
+                self.field(15) as u8,
+                self.field(16_383) as u16,
+                self.field(511) as u16,
+            )
+            .unwrap()
+        }
+    }
+
+    /// The fields of `code` in hierarchical order, without the data region.
+    fn hierarchical_key(code: FIPSCode) -> (u8, u16, u32, u8, u16) {
+        (
+            code.state_code().get(),
+            code.county_code(),
+            code.census_tract_code(),
+            code.category_code(),
+            code.id(),
+        )
+    }
+
+    #[test]
+    fn test_numeric_order_is_hierarchical() {
+        let mut rng = SplitMix64(0x5EED);
+        for _ in 0..10_000 {
+            let (a, b) = (rng.code(), rng.code());
+            let hierarchical = hierarchical_key(a).cmp(&hierarchical_key(b));
+            assert_eq!(a.compare_non_data(b), hierarchical, "{a:?} vs {b:?}");
+            // The data region only breaks ties between codes with the same hierarchical fields.
+            let numeric = a.as_u64().cmp(&b.as_u64());
+            assert_eq!(
+                numeric,
+                hierarchical.then(a.data().cmp(&b.data())),
+                "{a:?} vs {b:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_sort_codes() {
+        let mut rng = SplitMix64(0xC0DE);
+        let mut codes: Vec<FIPSCode> = (0..1_000).map(|_| rng.code()).collect();
+        let mut numeric = codes.clone();
+        numeric.sort_by_key(FIPSCode::as_u64);
+        sort_codes(&mut codes);
+
+        let keys: Vec<_> = codes.iter().copied().map(hierarchical_key).collect();
+        assert!(keys.is_sorted());
+        let numeric_keys: Vec<_> = numeric.iter().copied().map(hierarchical_key).collect();
+        assert_eq!(keys, numeric_keys);
+
+        // Stable with respect to the data region
+        let a = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let b = FIPSCode::with_tract(USState::AL.into(), 1, 100).unwrap();
+        let mut codes = [a.set_data(7).unwrap(), b, a.set_data(2).unwrap()];
+        sort_codes(&mut codes);
+        assert_eq!(codes, [b, a.set_data(7).unwrap(), a.set_data(2).unwrap()]);
+    }
+
+    #[test]
+    fn test_dedup_non_data() {
+        let a = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let b = FIPSCode::with_tract(USState::TX.into(), 201, 223_200).unwrap();
+        let mut codes = vec![
+            a,
+            a.set_data(1).unwrap(),
+            a.set_data(2).unwrap(),
+            b.set_data(3).unwrap(),
+            b,
+            a,
+        ];
+
+        dedup_non_data(&mut codes);
+        assert_eq!(codes, vec![a, b.set_data(3).unwrap(), a]);
+
+        let mut empty: Vec<FIPSCode> = vec![];
+        dedup_non_data(&mut empty);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_fips_set_diff() {
+        let a = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let b = FIPSCode::with_tract(USState::TX.into(), 201, 223_200).unwrap();
+        let c = FIPSCode::with_county(USState::WY.into(), 1).unwrap();
+
+        let old: BTreeSet<FIPSCode> = [a, b].into_iter().collect();
+        // `a` differs only in its data, so it is neither added nor removed.
+        let new: BTreeSet<FIPSCode> = [a.set_data(5).unwrap(), c].into_iter().collect();
+
+        let (added, removed) = fips_set_diff(&old, &new);
+        assert_eq!(added, vec![c]);
+        assert_eq!(removed, vec![b]);
+
+        let (added, removed) = fips_set_diff(&old, &old);
+        assert!(added.is_empty() && removed.is_empty());
+    }
+
+    #[test]
+    fn test_interner() {
+        let a = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let b = FIPSCode::with_county(USState::WY.into(), 1).unwrap();
+        let mut interner = FIPSInterner::new();
+        assert!(interner.is_empty());
+
+        assert_eq!(interner.intern(a), 0);
+        assert_eq!(interner.intern(b), 1);
+        assert_eq!(interner.intern(a), 0);
+        assert_eq!(interner.len(), 2);
+
+        assert_eq!(interner.resolve(0), Some(a));
+        assert_eq!(interner.resolve(1), Some(b));
+        assert_eq!(interner.resolve(2), None);
+    }
+
+    #[cfg(feature = "fast_hash")]
+    #[test]
+    fn test_fips_map() {
+        let mut map: FIPSMap<usize> = FIPSMap::default();
+        let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let homes: Vec<FIPSCode> = tract.id_range(1000).unwrap().collect();
+
+        for (index, home) in homes.iter().enumerate() {
+            map.insert(*home, index);
+        }
+        *map.entry(tract).or_default() += 7;
+
+        assert_eq!(map.len(), 1001);
+        assert_eq!(map[&tract], 7);
+        for (index, home) in homes.iter().enumerate() {
+            assert_eq!(map.get(home), Some(&index));
+        }
+        assert_eq!(map.remove(&homes[0]), Some(0));
+        assert!(!map.contains_key(&homes[0]));
+        assert!(!map.contains_key(&FIPSCode::with_state(USState::WY)));
+    }
+}
@@ -75,13 +75,113 @@
 use crate::{
     states::USState, CountyCode, DataCode, IdCode, SettingCategoryCode, StateCode, TractCode,
     CATEGORY_OFFSET, COUNTY_OFFSET, FOURTEEN_BIT_MASK, FOUR_BIT_MASK, ID_OFFSET, NINE_BIT_MASK,
-    SEVEN_BIT_MASK, STATE_OFFSET, TEN_BIT_MASK, TRACT_OFFSET, TWENTY_BIT_MASK,
+    STATE_OFFSET, TEN_BIT_MASK, TRACT_OFFSET, TWENTY_BIT_MASK,
 };
 use std::{
     cmp::Ordering,
-    fmt::{Debug, Display, Formatter},
+    fmt::{Debug, Display, Formatter, Write},
     num::NonZero,
 };
+#[cfg(feature = "geojson")]
+use std::collections::BTreeMap;
+
+/// The name, offset, and width of a bit field of the encoding of a `FIPSCode`. The field occupies bits
+/// `offset..offset + bits` of the encoded `u64`, counting from the least significant bit.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub offset: usize,
+    pub bits: usize,
+}
+
+/// The fields of the encoding of a `FIPSCode`, from the most significant to the least, as in the table in the
+/// [module level documentation](`crate::fips_code`). The fields are contiguous and together occupy all 64 bits.
+pub const FIELDS: &[FieldSpec] = &[
+    FieldSpec {
+        name: "state",
+        offset: STATE_OFFSET,
+        bits: 7,
+    },
+    FieldSpec {
+        name: "county",
+        offset: COUNTY_OFFSET,
+        bits: 10,
+    },
+    FieldSpec {
+        name: "tract",
+        offset: TRACT_OFFSET,
+        bits: 20,
+    },
+    FieldSpec {
+        name: "category",
+        offset: CATEGORY_OFFSET,
+        bits: 4,
+    },
+    FieldSpec {
+        name: "id",
+        offset: ID_OFFSET,
+        bits: 14,
+    },
+    FieldSpec {
+        name: "data",
+        offset: 0,
+        bits: 9,
+    },
+];
+
+/// The error returned by the fallible constructors and setters of `FIPSCode` when a field cannot be encoded.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FIPSCodeError {
+    /// The value `value` of the field named `field` exceeds `max`, the largest value that fits in the field's bits.
+    FieldOverflow {
+        field: &'static str,
+        value: u64,
+        max: u64,
+    },
+    /// The state code `value` is not a valid `StateCode`, that is, it is zero or does not fit in 7 bits, or, where a
+    /// `USState` is required, it is not the code of one of the `USState`s, e.g. the unassigned code 3.
+    InvalidStateCode { value: u8 },
+    /// The signed value `value` of the field named `field` is outside the range `min..=max` of the field's two's
+    /// complement encoding.
+    SignedFieldOverflow {
+        field: &'static str,
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+    /// The data region holds `value`, which is not a valid value of the type it was read as.
+    InvalidData { value: DataCode },
+}
+
+impl Display for FIPSCodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FIPSCodeError::FieldOverflow { field, value, max } => {
+                write!(f, "{} code {} exceeds the maximum of {}", field, value, max)
+            }
+            FIPSCodeError::InvalidStateCode { value } => {
+                write!(f, "{} is not a valid state code", value)
+            }
+            FIPSCodeError::SignedFieldOverflow {
+                field,
+                value,
+                min,
+                max,
+            } => {
+                write!(
+                    f,
+                    "{} value {} is outside the range {} to {}",
+                    field, value, min, max
+                )
+            }
+            FIPSCodeError::InvalidData { value } => {
+                write!(f, "data {} is not a valid value of its type", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FIPSCodeError {}
 
 /// Encodes a hierarchical FIPS geographic region code in 64 bits. Excludes the nonhierarchical codes places,
 /// congressional or state legislative districts, and ZIP code tabulation areas. (See the
@@ -97,31 +197,75 @@ impl FIPSCode {
         Self::new(state.into(), 0, 0, 0, 0, 0).unwrap()
     }
     /// Constructs a new `FIPSCode`.
-    /// Returns `Err(())` if the data provided is out of range.
-    pub fn with_state_code(state_code: StateCode) -> Result<Self, ()> {
-        Self::new(state_code, 0, 0, 0, 0, 0)
+    #[must_use]
+    pub fn with_state_code(state_code: StateCode) -> Self {
+        // A `StateCode` is always in range, so unwrap will succeed.
+        Self::new(state_code, 0, 0, 0, 0, 0).unwrap()
     }
     /// Constructs a new `FIPSCode`.
-    /// Returns `Err(())` if the data provided is out of range.
-    pub fn with_county(state: StateCode, county: CountyCode) -> Result<Self, ()> {
+    /// Returns `FIPSCodeError::FieldOverflow` if the data provided is out of range.
+    pub fn with_county(state: StateCode, county: CountyCode) -> Result<Self, FIPSCodeError> {
         Self::new(state, county, 0, 0, 0, 0)
     }
     /// Constructs a new `FIPSCode`.
-    /// Returns `Err(())` if the data provided is out of range.
-    pub fn with_tract(state: StateCode, county: CountyCode, tract: TractCode) -> Result<Self, ()> {
+    /// Returns `FIPSCodeError::FieldOverflow` if the data provided is out of range.
+    pub fn with_tract(
+        state: StateCode,
+        county: CountyCode,
+        tract: TractCode,
+    ) -> Result<Self, FIPSCodeError> {
         Self::new(state, county, tract, 0, 0, 0)
     }
+    /// Constructs the tract-level `FIPSCode` for a bare, county-relative census tract code, such as one read with
+    /// `parse_bare_tract_code`, whose state and county are known from context, e.g. the file it was read from.
+    /// Returns `FIPSCodeError::FieldOverflow` if the data provided is out of range.
+    pub fn with_context(
+        state: StateCode,
+        county: CountyCode,
+        bare_tract: TractCode,
+    ) -> Result<Self, FIPSCodeError> {
+        Self::with_tract(state, county, bare_tract)
+    }
     /// Constructs a new `FIPSCode`.
-    /// Returns `Err(())` if the data provided is out of range.
+    /// Returns `FIPSCodeError::FieldOverflow` if the data provided is out of range.
     pub fn with_category(
         state: StateCode,
         county: CountyCode,
         tract: TractCode,
         category: SettingCategoryCode,
-    ) -> Result<Self, ()> {
+    ) -> Result<Self, FIPSCodeError> {
         Self::new(state, county, tract, category, 0, 0)
     }
+    /// Constructs the tract-level `FIPSCode` for `state`, `county`, and `tract`.
+    /// Returns `FIPSCodeError::FieldOverflow` if the data provided is out of range. See `FIPSCode::new_unchecked` for a
+    /// version without the range checks.
+    pub fn try_new(
+        state: USState,
+        county: CountyCode,
+        tract: TractCode,
+    ) -> Result<Self, FIPSCodeError> {
+        Self::with_tract(state.into(), county, tract)
+    }
+    /// Constructs the tract-level `FIPSCode` for `state`, `county`, and `tract` without checking that `county` and
+    /// `tract` are in range, for hot paths whose inputs have already been validated. The caller must guarantee that
+    /// `county` fits in 10 bits and `tract` in 20 bits; otherwise the excess bits spill into the neighboring fields and
+    /// the result is a different, valid-looking code. Debug builds still check the ranges and panic if they are violated.
+    /// Use `FIPSCode::try_new` for unvalidated inputs.
+    #[inline(always)]
+    #[must_use]
+    pub fn new_unchecked(state: USState, county: CountyCode, tract: TractCode) -> Self {
+        debug_assert!(county <= TEN_BIT_MASK, "county code {county} out of range");
+        debug_assert!(tract <= TWENTY_BIT_MASK, "tract code {tract} out of range");
+        let encoded: u64 = Self::encode_state(state.into())
+            | (county as u64) << COUNTY_OFFSET
+            | (tract as u64) << TRACT_OFFSET;
+        // The state code is nonzero, so this unwrapping is safe.
+        Self(NonZero::new(encoded).unwrap())
+    }
 
+    /// Constructs a new `FIPSCode` from all of its fields.
+    /// Returns `FIPSCodeError::FieldOverflow` for the first field, in order from the state down, that does not fit in
+    /// its bits.
     pub fn new(
         state: StateCode,
         county: CountyCode,
@@ -129,8 +273,8 @@ impl FIPSCode {
         category: SettingCategoryCode,
         id: IdCode,
         data: DataCode,
-    ) -> Result<Self, ()> {
-        let encoded: u64 = Self::encode_state(state)?
+    ) -> Result<Self, FIPSCodeError> {
+        let encoded: u64 = Self::encode_state(state)
             | Self::encode_county(county)?
             | Self::encode_tract(tract)?
             | Self::encode_category(category)?
@@ -140,40 +284,76 @@ impl FIPSCode {
         let encoded = NonZero::new(encoded).unwrap();
         Ok(Self(encoded))
     }
+
+    /// Reconstructs a `FIPSCode` from its raw 64-bit encoding as returned by `FIPSCode::as_u64`. Every other field fills
+    /// its bits exactly, so the only invalid encodings are those with state code 0, for which this returns
+    /// `FIPSCodeError::InvalidStateCode`.
+    pub fn from_u64(bits: u64) -> Result<Self, FIPSCodeError> {
+        let state_code = state_code_from_u64(bits);
+        if StateCode::new(state_code).is_none() {
+            return Err(FIPSCodeError::InvalidStateCode { value: state_code });
+        }
+        // The state code is nonzero, so this unwrapping is safe.
+        Ok(Self(NonZero::new(bits).unwrap()))
+    }
+
+    /// Applies `FIPSCode::from_u64` to each of `bits`, e.g. to validate a column of encodings while collecting every
+    /// failure.
+    #[must_use]
+    pub fn from_u64_slice(bits: &[u64]) -> Vec<Result<Self, FIPSCodeError>> {
+        bits.iter().map(|&bits| Self::from_u64(bits)).collect()
+    }
+
+    /// Like `FIPSCode::from_u64_slice`, but stops at the first invalid encoding, returning its index in `bits` along
+    /// with the error.
+    pub fn try_from_u64_slice(bits: &[u64]) -> Result<Vec<Self>, (usize, FIPSCodeError)> {
+        bits.iter()
+            .enumerate()
+            .map(|(index, &bits)| Self::from_u64(bits).map_err(|error| (index, error)))
+            .collect()
+    }
     // endregion Constructors
 
     // region Accessors
 
     /// Returns the FIPS STATE as a `USState` enum variant.
-    /// Returns `Err(())` if 'USState' cannot represent the state code. Use `FIPSCode::state_code()` to
-    /// retrieve the state code in this case.
+    /// Returns `FIPSCodeError::InvalidStateCode` if `USState` cannot represent the state code. Use
+    /// `FIPSCode::state_code()` to retrieve the state code in this case.
+    #[inline(always)]
+    pub fn state(&self) -> Result<USState, FIPSCodeError> {
+        USState::try_from(self.state_code()).map_err(|()| FIPSCodeError::InvalidStateCode {
+            value: self.state_code().get(),
+        })
+    }
+
+    /// Returns the raw 64-bit encoding of the code. Numerical order of these values coincides with hierarchical order
+    /// (see the [module level documentation](`crate::fips_code`)).
     #[inline(always)]
-    pub fn state(&self) -> Result<USState, ()> {
-        USState::decode(self.state_code())
+    #[must_use]
+    pub const fn as_u64(&self) -> u64 {
+        self.0.get()
     }
 
-    /// Returns the FIPS STATE code as a `StateCode` (a `u8`)
+    /// Returns the FIPS STATE code as a `StateCode`
     #[inline(always)]
     #[must_use]
     pub fn state_code(&self) -> StateCode {
-        // The state code occupies the 7 most significant bits, bits 57..63
-        (self.0.get() >> STATE_OFFSET) as StateCode
+        // The state code of a valid `FIPSCode` is a valid `StateCode`.
+        StateCode(state_code_from_u64(self.0.get()))
     }
 
     /// Returns the numeric FIPS COUNTY code
     #[inline(always)]
     #[must_use]
     pub fn county_code(&self) -> CountyCode {
-        // The county code occupies the 10 bits from bits 47..56
-        ((self.0.get() >> COUNTY_OFFSET) as CountyCode) & TEN_BIT_MASK
+        county_code_from_u64(self.0.get())
     }
 
     /// Returns the numeric FIPS CENSUS TRACT code
     #[inline(always)]
     #[must_use]
     pub fn census_tract_code(&self) -> TractCode {
-        // The census tract code occupies the 20 bits from bits 27..46
-        ((self.0.get() >> TRACT_OFFSET) as TractCode) & TWENTY_BIT_MASK
+        tract_code_from_u64(self.0.get())
     }
 
     /// Returns the numeric SETTING CATEGORY code
@@ -198,6 +378,22 @@ impl FIPSCode {
     pub fn data(&self) -> DataCode {
         self.0.get() as DataCode & NINE_BIT_MASK
     }
+
+    /// Returns `true` if any bit of the data region (the 9 LSB) is set, that is, if the producer of this code used the
+    /// application-specific region.
+    #[inline(always)]
+    #[must_use]
+    pub fn has_data(&self) -> bool {
+        self.data() != 0
+    }
+
+    /// Returns the unused data region interpreted as an application-specific type `T`, for example an enum packed into
+    /// the data region with `FIPSCode::set_data_typed`.
+    /// Returns `FIPSCodeError::InvalidData` if the data region does not hold a valid `T`.
+    #[inline(always)]
+    pub fn data_typed<T: TryFrom<DataCode>>(&self) -> Result<T, FIPSCodeError> {
+        T::try_from(self.data()).map_err(|_| FIPSCodeError::InvalidData { value: self.data() })
+    }
     // endregion Accessors
 
     // region Setters
@@ -205,57 +401,143 @@ impl FIPSCode {
     /// Creates a copy of `self` with the FIPS STATE set to `state`.
     #[must_use]
     pub fn set_state(&self, state: USState) -> Self {
-        self.set_state_code(state.into()).unwrap()
+        self.set_state_code(state.into())
     }
 
     /// Creates a copy of `self` with the FIPS STATE set to `state`.
-    pub fn set_state_code(&self, state_code: StateCode) -> Result<Self, ()> {
+    #[must_use]
+    pub fn set_state_code(&self, state_code: StateCode) -> Self {
         let mut expanded = ExpandedFIPSCode::from_fips_code(*self);
         expanded.state = state_code;
-        expanded.to_fips_code()
+        // The other fields come from a valid `FIPSCode`, so unwrap will succeed.
+        expanded.to_fips_code().unwrap()
     }
 
     /// Creates a copy of `self` with the FIPS COUNTY set to `county`.
-    pub fn set_county(&self, county: CountyCode) -> Result<Self, ()> {
+    pub fn set_county(&self, county: CountyCode) -> Result<Self, FIPSCodeError> {
         let mut expanded = ExpandedFIPSCode::from_fips_code(*self);
         expanded.county = county;
         expanded.to_fips_code()
     }
 
     /// Creates a copy of `self` with the FIPS CENSUS TRACT set to `tract`.
-    pub fn set_tract(&self, tract: TractCode) -> Result<Self, ()> {
+    pub fn set_tract(&self, tract: TractCode) -> Result<Self, FIPSCodeError> {
         let mut expanded = ExpandedFIPSCode::from_fips_code(*self);
         expanded.tract = tract;
         expanded.to_fips_code()
     }
 
     /// Creates a copy of `self` with the setting category set to `category`.
-    pub fn set_category(&self, category: SettingCategoryCode) -> Result<Self, ()> {
+    pub fn set_category(&self, category: SettingCategoryCode) -> Result<Self, FIPSCodeError> {
         let mut expanded = ExpandedFIPSCode::from_fips_code(*self);
         expanded.category = category;
         expanded.to_fips_code()
     }
 
+    /// Creates a copy of `self` with the setting category (bits 26…23) zeroed and every other field, including the id,
+    /// preserved.
+    #[inline(always)]
+    #[must_use]
+    pub fn without_category(&self) -> Self {
+        let inverse_mask = !((FOUR_BIT_MASK as u64) << CATEGORY_OFFSET);
+        // The state code is never zero and is never masked, so unwrap will succeed.
+        Self(NonZero::new(self.0.get() & inverse_mask).unwrap())
+    }
+
     /// Creates a copy of `self` with the ID number set to `id`.
-    pub fn set_id(&self, id: IdCode) -> Result<Self, ()> {
+    pub fn set_id(&self, id: IdCode) -> Result<Self, FIPSCodeError> {
         let mut expanded = ExpandedFIPSCode::from_fips_code(*self);
         expanded.id = id;
         expanded.to_fips_code()
     }
 
     /// Creates a copy of `self` with the unused data region set to `data`.
-    pub fn set_data(&self, data: DataCode) -> Result<Self, ()> {
+    pub fn set_data(&self, data: DataCode) -> Result<Self, FIPSCodeError> {
         let mut expanded = ExpandedFIPSCode::from_fips_code(*self);
         expanded.data = data;
         expanded.to_fips_code()
     }
 
+    /// Creates a copy of `self` with the unused data region (bits 8…0) zeroed and every other field preserved.
+    #[inline(always)]
+    #[must_use]
+    pub fn without_data(&self) -> Self {
+        let inverse_mask = !(NINE_BIT_MASK as u64);
+        // The state code is never zero and is never masked, so unwrap will succeed.
+        Self(NonZero::new(self.0.get() & inverse_mask).unwrap())
+    }
+
+    /// Creates a copy of `self` with the unused data region set to the application-specific value `data`.
+    /// Returns `FIPSCodeError::FieldOverflow` if `data` does not fit in the data region's 9 bits.
+    pub fn set_data_typed<T: Into<DataCode>>(&self, data: T) -> Result<Self, FIPSCodeError> {
+        self.set_data(data.into())
+    }
+
+    /// Creates a copy of `self` with the ID number replaced by `f` applied to the current ID number, e.g. to renumber
+    /// codes in an iterator chain. Returns `FIPSCodeError::FieldOverflow` if the new ID number does not fit in 14 bits.
+    pub fn map_id(&self, f: impl FnOnce(IdCode) -> IdCode) -> Result<Self, FIPSCodeError> {
+        self.set_id(f(self.id()))
+    }
+
+    /// Creates a copy of `self` with the data region replaced by `f` applied to its current value. Returns
+    /// `FIPSCodeError::FieldOverflow` if the new value does not fit in 9 bits.
+    pub fn map_data(&self, f: impl FnOnce(DataCode) -> DataCode) -> Result<Self, FIPSCodeError> {
+        self.set_data(f(self.data()))
+    }
+
     // endregion Setters
 
+    /// Returns an iterator over copies of `self` with the ID number set to each of `1..=count` in turn, e.g. to
+    /// enumerate every home in a census tract.
+    /// Returns `FIPSCodeError::FieldOverflow` if `count` exceeds the capacity of the ID field.
+    pub fn id_range(&self, count: IdCode) -> Result<impl Iterator<Item = Self>, FIPSCodeError> {
+        // Validating the largest ID validates all of them.
+        Self::encode_id(count)?;
+        let fips_code = *self;
+        // Each ID is in range, so unwrap will succeed.
+        Ok((1..=count).map(move |id| fips_code.set_id(id).unwrap()))
+    }
+
+    /// Returns an iterator over the tract-level codes in the county `county` of the state `state` for each tract code in
+    /// `tracts` in turn, e.g. to build an index of every tract in a county from a list of its tract codes.
+    /// Returns `FIPSCodeError::FieldOverflow` if `county` or any of `tracts` is out of range, before any code is
+    /// produced.
+    pub fn tracts_in_county(
+        state: StateCode,
+        county: CountyCode,
+        tracts: &[TractCode],
+    ) -> Result<impl Iterator<Item = Self> + '_, FIPSCodeError> {
+        let fips_code = Self::with_county(state, county)?;
+        for &tract in tracts {
+            Self::encode_tract(tract)?;
+        }
+        // Each tract is in range, so unwrap will succeed.
+        Ok(tracts
+            .iter()
+            .map(move |&tract| fips_code.set_tract(tract).unwrap()))
+    }
+
+    /// Returns an iterator over the county-level codes in the state `state` for each county code in `counties` in turn,
+    /// e.g. to enumerate the counties of a state from a reference table. Returns `FIPSCodeError::FieldOverflow` if any of
+    /// `counties` is out of range, before any code is produced.
+    pub fn counties_in_state(
+        state: StateCode,
+        counties: &[CountyCode],
+    ) -> Result<impl Iterator<Item = Self> + '_, FIPSCodeError> {
+        let fips_code = Self::with_state_code(state);
+        for &county in counties {
+            Self::encode_county(county)?;
+        }
+        // Each county is in range, so unwrap will succeed.
+        Ok(counties
+            .iter()
+            .map(move |&county| fips_code.set_county(county).unwrap()))
+    }
+
     /// Sets the unused data region occupying the 10 LSB in place.
-    /// Returns `Ok(())` if `data` is in range, `Err(())` otherwise.
+    /// Returns `FIPSCodeError::FieldOverflow` if `data` does not fit in the data region's 9 bits.
     #[inline(always)]
-    pub fn set_data_in_place(&mut self, data: u16) -> Result<(), ()> {
+    pub fn set_data_in_place(&mut self, data: u16) -> Result<(), FIPSCodeError> {
         if data <= NINE_BIT_MASK {
             let inverse_mask = !(NINE_BIT_MASK as u64);
             let code = (self.0.get() & inverse_mask) | ((data & NINE_BIT_MASK) as u64);
@@ -263,7 +545,39 @@ impl FIPSCode {
             self.0 = NonZero::new(code).unwrap();
             Ok(())
         } else {
-            Err(())
+            Err(FIPSCodeError::FieldOverflow {
+                field: "data",
+                value: data.into(),
+                max: NINE_BIT_MASK.into(),
+            })
+        }
+    }
+
+    /// Returns the data region interpreted as a 9-bit two's complement integer, in the range `-256..=255`, for
+    /// applications that store a small signed value there. This is the inverse of `FIPSCode::set_data_i16`.
+    #[inline(always)]
+    #[must_use]
+    pub fn data_as_i16(&self) -> i16 {
+        // Move the sign bit of the data region into the sign bit of the `i16` and shift back, extending the sign.
+        (self.data() << 7).cast_signed() >> 7
+    }
+
+    /// Sets the data region in place to `value` encoded as a 9-bit two's complement integer.
+    /// Returns `FIPSCodeError::SignedFieldOverflow` if `value` is not in the range `-256..=255`.
+    #[inline(always)]
+    pub fn set_data_i16(&mut self, value: i16) -> Result<(), FIPSCodeError> {
+        const MIN: i16 = -256;
+        const MAX: i16 = 255;
+        if (MIN..=MAX).contains(&value) {
+            // Truncating to the 9 LSB gives the two's complement encoding, which always fits.
+            self.set_data_in_place(value.cast_unsigned() & NINE_BIT_MASK)
+        } else {
+            Err(FIPSCodeError::SignedFieldOverflow {
+                field: "data",
+                value: value.into(),
+                min: MIN.into(),
+                max: MAX.into(),
+            })
         }
     }
 
@@ -279,73 +593,354 @@ impl FIPSCode {
         this.cmp(&other)
     }
 
+    /// Returns `true` if `self` and `other` have the same state, county, and census tract, that is, if bits 63…27
+    /// agree. The category (bits 26…23), id (bits 22…9), and data (bits 8…0) fields are ignored, so, for example, a
+    /// home and a workplace in the same census tract are at the same location.
+    #[inline(always)]
+    #[must_use]
+    pub fn same_location(&self, other: Self) -> bool {
+        self.parent_tract() == other.parent_tract()
+    }
+
+    /// Returns `true` if `self` and `other` have the same state and county, that is, if bits 63…47 agree. All other
+    /// fields are ignored.
+    #[inline(always)]
+    #[must_use]
+    pub fn same_county(&self, other: Self) -> bool {
+        self.parent_county() == other.parent_county()
+    }
+
+    /// Returns the state and county of `self` packed into the low 17 bits of a `u32`, state above county, so that keys
+    /// order by state and then county. Codes in the same county share a key; all other fields are ignored.
+    #[inline(always)]
+    #[must_use]
+    pub const fn county_sort_key(&self) -> u32 {
+        (self.0.get() >> COUNTY_OFFSET) as u32
+    }
+
+    /// Returns `true` if the state and county of `self` are both specified (nonzero), as opposed to `self` being a
+    /// state-level prefix. (The state of a `FIPSCode` is always specified.)
+    #[inline(always)]
+    #[must_use]
+    pub fn is_complete_county(&self) -> bool {
+        self.county_code() != 0
+    }
+
+    /// Returns `true` if the state, county, and census tract of `self` are all specified (nonzero), as opposed to
+    /// `self` being a state- or county-level prefix.
+    #[inline(always)]
+    #[must_use]
+    pub fn is_complete_tract(&self) -> bool {
+        self.is_complete_county() && self.census_tract_code() != 0
+    }
+
+    // region Truncation
+
+    /// Returns the tract-level code containing `self`, that is, `self` with the category, id, and data fields zeroed.
+    #[inline(always)]
+    #[must_use]
+    pub fn parent_tract(&self) -> Self {
+        self.truncate_to(TRACT_OFFSET)
+    }
+
+    /// Returns the county-level code containing `self`, that is, `self` with every field below the county zeroed.
+    #[inline(always)]
+    #[must_use]
+    pub fn parent_county(&self) -> Self {
+        self.truncate_to(COUNTY_OFFSET)
+    }
+
+    /// Returns the state-level code containing `self`, that is, `self` with every field below the state zeroed.
+    #[inline(always)]
+    #[must_use]
+    pub fn parent_state(&self) -> Self {
+        self.truncate_to(STATE_OFFSET)
+    }
+
+    /// Zeroes every bit below `offset`. Since `offset` is the offset of one of the hierarchical fields, the result
+    /// is the code at that level of the hierarchy containing `self`.
+    #[inline(always)]
+    fn truncate_to(self, offset: usize) -> Self {
+        let mask = u64::MAX << offset;
+        // The state code is never zero and is never masked, so unwrap will succeed.
+        Self(NonZero::new(self.0.get() & mask).unwrap())
+    }
+
+    /// Returns the inclusive bounds `(min, max)` of the raw encodings (see `FIPSCode::as_u64`) of every code under
+    /// the prefix `self`, e.g. for a `BETWEEN` query against a column of packed codes. The prefix is `self` truncated
+    /// after its most specific nonzero field, so for a county-level code the bounds span every tract, category, id, and
+    /// data value in the county. A code with nonzero data is its own only descendant.
+    #[must_use]
+    pub fn prefix_bounds(&self) -> (u64, u64) {
+        let bits = self.as_u64();
+        // The largest field offset below which every bit is zero. The data field has offset 0, which always matches.
+        let offset = [
+            STATE_OFFSET,
+            COUNTY_OFFSET,
+            TRACT_OFFSET,
+            CATEGORY_OFFSET,
+            ID_OFFSET,
+            0,
+        ]
+        .into_iter()
+        .find(|&offset| bits & !(u64::MAX << offset) == 0)
+        .unwrap();
+        (bits, bits | !(u64::MAX << offset))
+    }
+
+    /// Returns the shard, in `0..num_shards`, of the census tract containing `self`, for distributing records across
+    /// `num_shards` workers by geography. Only the state, county, and tract fields are hashed, so every code in a tract
+    /// lands on the same shard. The hash is a fixed function of the encoding, so the assignment is the same across runs
+    /// and platforms.
+    ///
+    /// Panics if `num_shards` is zero.
+    #[must_use]
+    pub fn geo_shard(&self, num_shards: u32) -> u32 {
+        assert!(num_shards > 0, "the number of shards must be positive");
+        let tract_bits = self.parent_tract().as_u64() >> TRACT_OFFSET;
+        // Fibonacci hashing, as in `FIPSHasher`: the high bits of the product depend on every bit of the tract.
+        let hash = (tract_bits.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32) as u32;
+        hash % num_shards
+    }
+
+    // endregion Truncation
+
+    /// Returns a human-readable description of `self` for reports, for example
+    /// `"Texas, County 201, Tract 2231 (Setting 1 #24)"`. The state is described by its full name when it is one of
+    /// the `USState`s. County names are not available, so the county is described by its numeric code. The tract is
+    /// described by its Census name, i.e. with the implied decimal point of the tract code, so that tract code
+    /// `950101` is described as `"Tract 9501.01"`. Fields below the state are omitted when they are zero.
+    ///
+    /// The meaning of the setting category is defined by the application, so the category is described by its numeric
+    /// code. For ASPR ids, `ixa_aspr::describe_id` describes it by name instead, e.g. `"(Home #24)"`.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        // Writing to a `String` is infallible.
+        let mut description = match self.state() {
+            Ok(state) => state.name().to_string(),
+            Err(_) => format!("State {:02}", self.state_code()),
+        };
+        if self.county_code() != 0 {
+            write!(description, ", County {:03}", self.county_code()).unwrap();
+        }
+        let tract = self.census_tract_code();
+        if tract != 0 {
+            write!(description, ", Tract {}", census_tract_name(tract)).unwrap();
+        }
+        if self.category_code() != 0 || self.id() != 0 {
+            write!(
+                description,
+                " (Setting {} #{})",
+                self.category_code(),
+                self.id()
+            )
+            .unwrap();
+        }
+        description
+    }
+
+    /// Returns a compact representation of `self` for log lines consisting of the nonzero fields joined by slashes,
+    /// for example `"TX/201/223100"` for a census tract. The state is given by its abbreviation when it is one of the
+    /// `USState`s, and the county and census tract codes are zero-padded to their usual widths of 3 and 6 digits.
+    #[must_use]
+    pub fn slash_path(&self) -> String {
+        // Writing to a `String` is infallible.
+        let mut path = match self.state() {
+            Ok(state) => state.as_ref().to_string(),
+            Err(_) => format!("{:02}", self.state_code()),
+        };
+        if self.county_code() != 0 {
+            write!(path, "/{:03}", self.county_code()).unwrap();
+        }
+        if self.census_tract_code() != 0 {
+            write!(path, "/{:06}", self.census_tract_code()).unwrap();
+        }
+        for value in [self.category_code() as u16, self.id(), self.data()] {
+            if value != 0 {
+                write!(path, "/{}", value).unwrap();
+            }
+        }
+        path
+    }
+
+    /// Returns one labeled segment for each populated geographic level of `self`, from the state down, for example
+    /// `[(State, "TX"), (County, "201"), (Tract, "2231")]` for a census tract. The labels are as in
+    /// `FIPSCode::describe`, except that the state is given by its abbreviation when it is one of the `USState`s. The
+    /// category, id, and data fields are not geographic levels and are omitted.
+    #[must_use]
+    pub fn breadcrumb(&self) -> Vec<(GeographicLevel, String)> {
+        let state = match self.state() {
+            Ok(state) => state.as_ref().to_string(),
+            Err(_) => format!("{:02}", self.state_code()),
+        };
+        let mut segments = vec![(GeographicLevel::State, state)];
+        if self.county_code() != 0 {
+            let county = format!("{:03}", self.county_code());
+            segments.push((GeographicLevel::County, county));
+        }
+        let tract = self.census_tract_code();
+        if tract != 0 {
+            segments.push((GeographicLevel::Tract, census_tract_name(tract)));
+        }
+        segments
+    }
+
+    /// Returns the Census Data API query parameters selecting the geography of `self`, e.g.
+    /// `[("state", "48"), ("county", "201"), ("tract", "223100")]` for a census tract, with values zero-padded to 2, 3,
+    /// and 6 digits. The county and tract parameters are only emitted when populated, and the API requires a county
+    /// with a tract, so the tract is omitted when the county is not populated.
+    #[must_use]
+    pub fn to_census_api_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("state", format!("{:02}", self.state_code().get()))];
+        if self.is_complete_county() {
+            params.push(("county", format!("{:03}", self.county_code())));
+        }
+        if self.is_complete_tract() {
+            params.push(("tract", format!("{:06}", self.census_tract_code())));
+        }
+        params
+    }
+
+    /// Returns the properties of `self` as JSON values, to attach to a map feature whose geometry comes from elsewhere,
+    /// e.g. a Census cartographic boundary file: `"GEOID"`, the GEOID of the most specific populated geographic level,
+    /// such as `"48201223100"` for a census tract, the zero-padded `"state"`, `"county"`, and `"tract"` codes of the
+    /// populated levels as in `FIPSCode::to_census_api_params`, and the numeric `"category"` code.
+    #[cfg(feature = "geojson")]
+    #[must_use]
+    pub fn to_property_map(&self) -> BTreeMap<&'static str, serde_json::Value> {
+        let params = self.to_census_api_params();
+        let geoid: String = params.iter().map(|(_, value)| value.as_str()).collect();
+        let mut properties: BTreeMap<&'static str, serde_json::Value> = params
+            .into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect();
+        properties.insert("GEOID", geoid.into());
+        properties.insert("category", self.category_code().into());
+        properties
+    }
+
     // region Encoding
     // It is convenient to factor out the encode operations into their own functions.
     // These functions take numeric values and return encoded `u64` values. To encode
     // enum variants, call the `encode` function on the enum variant.
 
     #[inline(always)]
-    fn encode_state(state: StateCode) -> Result<u64, ()> {
-        // Validate
-        if state <= SEVEN_BIT_MASK && state != 0 {
-            Ok((state as u64) << STATE_OFFSET)
-        } else {
-            Err(())
-        }
+    fn encode_state(state: StateCode) -> u64 {
+        // A `StateCode` is validated on construction.
+        (state.get() as u64) << STATE_OFFSET
     }
 
     #[inline(always)]
-    fn encode_county(county: CountyCode) -> Result<u64, ()> {
+    fn encode_county(county: CountyCode) -> Result<u64, FIPSCodeError> {
         // Validate
         if county <= TEN_BIT_MASK {
             Ok((county as u64) << COUNTY_OFFSET)
         } else {
-            Err(())
+            Err(FIPSCodeError::FieldOverflow {
+                field: "county",
+                value: county.into(),
+                max: TEN_BIT_MASK.into(),
+            })
         }
     }
 
     #[inline(always)]
-    fn encode_tract(tract: TractCode) -> Result<u64, ()> {
+    fn encode_tract(tract: TractCode) -> Result<u64, FIPSCodeError> {
         // Validate
         if tract <= TWENTY_BIT_MASK {
             Ok((tract as u64) << TRACT_OFFSET)
         } else {
-            Err(())
+            Err(FIPSCodeError::FieldOverflow {
+                field: "tract",
+                value: tract.into(),
+                max: TWENTY_BIT_MASK.into(),
+            })
         }
     }
 
     #[inline(always)]
-    fn encode_category(setting_category: SettingCategoryCode) -> Result<u64, ()> {
+    fn encode_category(setting_category: SettingCategoryCode) -> Result<u64, FIPSCodeError> {
         // Validate
         if setting_category <= FOUR_BIT_MASK {
             Ok((setting_category as u64) << CATEGORY_OFFSET)
         } else {
-            Err(())
+            Err(FIPSCodeError::FieldOverflow {
+                field: "category",
+                value: setting_category.into(),
+                max: FOUR_BIT_MASK.into(),
+            })
         }
     }
 
     #[inline(always)]
-    fn encode_id(id: IdCode) -> Result<u64, ()> {
+    fn encode_id(id: IdCode) -> Result<u64, FIPSCodeError> {
         // Validate
         if id <= FOURTEEN_BIT_MASK {
             Ok((id as u64) << ID_OFFSET)
         } else {
-            Err(())
+            Err(FIPSCodeError::FieldOverflow {
+                field: "id",
+                value: id.into(),
+                max: FOURTEEN_BIT_MASK.into(),
+            })
         }
     }
 
     #[inline(always)]
-    fn encode_data(data: DataCode) -> Result<u64, ()> {
+    fn encode_data(data: DataCode) -> Result<u64, FIPSCodeError> {
         // Validate
         if data <= NINE_BIT_MASK {
             Ok(data as u64)
         } else {
-            Err(())
+            Err(FIPSCodeError::FieldOverflow {
+                field: "data",
+                value: data.into(),
+                max: NINE_BIT_MASK.into(),
+            })
         }
     }
     // endregion Encoding
 }
 
+// region Raw Field Extraction
+// These functions extract fields directly from a packed `u64` encoded as described in the module level documentation,
+// without constructing a `FIPSCode`. They perform no validation, which makes them suitable for hot loops that filter
+// large arrays of packed codes.
+
+/// Returns the numeric FIPS STATE code from the packed `u64` representation of a `FIPSCode`. Unlike
+/// `FIPSCode::state_code`, this returns a raw `u8`, as `bits` is not validated.
+#[inline(always)]
+#[must_use]
+pub const fn state_code_from_u64(bits: u64) -> u8 {
+    // The state code occupies the 7 most significant bits, bits 57..63
+    (bits >> STATE_OFFSET) as u8
+}
+
+/// Returns the FIPS COUNTY code from the packed `u64` representation of a `FIPSCode`.
+#[inline(always)]
+#[must_use]
+pub const fn county_code_from_u64(bits: u64) -> CountyCode {
+    // The county code occupies the 10 bits from bits 47..56
+    ((bits >> COUNTY_OFFSET) as CountyCode) & TEN_BIT_MASK
+}
+
+/// Returns the FIPS CENSUS TRACT code from the packed `u64` representation of a `FIPSCode`.
+#[inline(always)]
+#[must_use]
+pub const fn tract_code_from_u64(bits: u64) -> TractCode {
+    // The census tract code occupies the 20 bits from bits 27..46
+    ((bits >> TRACT_OFFSET) as TractCode) & TWENTY_BIT_MASK
+}
+
+// endregion Raw Field Extraction
+
+impl From<FIPSCode> for u64 {
+    /// Returns the raw 64-bit encoding of the code. The field accessors `*_from_u64` decode this value.
+    fn from(fips_code: FIPSCode) -> Self {
+        fips_code.as_u64()
+    }
+}
+
 impl Display for FIPSCode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", ExpandedFIPSCode::from_fips_code(*self))
@@ -367,6 +962,35 @@ impl Debug for FIPSCode {
     }
 }
 
+/// Returns the Census name of the tract with code `tract`. Tract codes have an implied decimal point before the last two
+/// digits, which are omitted when zero, so that tract code `223100` is named `"2231"` and `950101` is named `"9501.01"`.
+fn census_tract_name(tract: TractCode) -> String {
+    if tract.is_multiple_of(100) {
+        format!("{}", tract / 100)
+    } else {
+        format!("{}.{:02}", tract / 100, tract % 100)
+    }
+}
+
+/// The geographic levels of the hierarchy encoded by a `FIPSCode`, from the broadest to the most specific.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+pub enum GeographicLevel {
+    State,
+    County,
+    Tract,
+}
+
+impl Display for GeographicLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            GeographicLevel::State => "State",
+            GeographicLevel::County => "County",
+            GeographicLevel::Tract => "Tract",
+        };
+        f.write_str(name)
+    }
+}
+
 /// A struct that holds an expanded version of a `FIPSCode` in which all fields are represented by
 /// their associated numeric types.
 ///
@@ -397,7 +1021,10 @@ impl ExpandedFIPSCode {
         }
     }
 
-    pub fn to_fips_code(&self) -> Result<FIPSCode, ()> {
+    /// Encodes `self` as a `FIPSCode`. Because the fields of an `ExpandedFIPSCode` are public and unchecked, the fields
+    /// are validated here. Returns `FIPSCodeError::FieldOverflow` if any field is out of range; this function never
+    /// panics.
+    pub fn to_fips_code(&self) -> Result<FIPSCode, FIPSCodeError> {
         FIPSCode::new(
             self.state,
             self.county,
@@ -412,7 +1039,7 @@ impl ExpandedFIPSCode {
 impl Display for ExpandedFIPSCode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         // Format the state if possible
-        if let Ok(state) = USState::decode(self.state) {
+        if let Ok(state) = USState::try_from(self.state) {
             write!(f, "state: {}", state.as_ref())?;
         } else {
             write!(f, "state: {}", self.state)?;
@@ -438,9 +1065,56 @@ impl Display for ExpandedFIPSCode {
     }
 }
 
+/// A `#[repr(C)]` decomposition of a `FIPSCode` into its fields, for passing codes across an FFI boundary without
+/// exposing the bit layout of the packed `u64`. As with `ExpandedFIPSCode`, it is up to the client code to ensure the
+/// field values are within range.
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct FIPSCodeC {
+    pub state: u8,
+    pub county: CountyCode,
+    pub tract: TractCode,
+    pub category: SettingCategoryCode,
+    pub id: IdCode,
+    pub data: DataCode,
+}
+
+impl FIPSCode {
+    /// Decomposes `self` into a `FIPSCodeC`.
+    #[must_use]
+    pub fn to_c(&self) -> FIPSCodeC {
+        FIPSCodeC {
+            state: self.state_code().get(),
+            county: self.county_code(),
+            tract: self.census_tract_code(),
+            category: self.category_code(),
+            id: self.id(),
+            data: self.data(),
+        }
+    }
+
+    /// Creates a `FIPSCode` from the fields of `fips_code_c`.
+    /// Returns `FIPSCodeError::InvalidStateCode` if the state code is not a valid `StateCode`, and
+    /// `FIPSCodeError::FieldOverflow` if any other field is out of range.
+    pub fn from_c(fips_code_c: FIPSCodeC) -> Result<Self, FIPSCodeError> {
+        let state = StateCode::new(fips_code_c.state).ok_or(FIPSCodeError::InvalidStateCode {
+            value: fips_code_c.state,
+        })?;
+        Self::new(
+            state,
+            fips_code_c.county,
+            fips_code_c.tract,
+            fips_code_c.category,
+            fips_code_c.id,
+            fips_code_c.data,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SEVEN_BIT_MASK;
 
     #[repr(u8)]
     enum SettingCategory {
@@ -457,11 +1131,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_signed_data() {
+        let mut fips_code = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        assert_eq!(fips_code.data_as_i16(), 0);
+
+        for value in [-256, -200, -1, 0, 1, 200, 255] {
+            fips_code.set_data_i16(value).unwrap();
+            assert_eq!(fips_code.data_as_i16(), value);
+            assert_eq!(fips_code.census_tract_code(), 223_100);
+        }
+        fips_code.set_data_i16(-1).unwrap();
+        assert_eq!(fips_code.data(), NINE_BIT_MASK);
+        fips_code.set_data_i16(-256).unwrap();
+        assert_eq!(fips_code.data(), 0x0100);
+
+        // The data region is 9 bits wide, so these do not fit.
+        assert_eq!(
+            fips_code.set_data_i16(-300),
+            Err(FIPSCodeError::SignedFieldOverflow {
+                field: "data",
+                value: -300,
+                min: -256,
+                max: 255,
+            })
+        );
+        assert!(matches!(
+            fips_code.set_data_i16(400),
+            Err(FIPSCodeError::SignedFieldOverflow { value: 400, .. })
+        ));
+        assert_eq!(fips_code.data_as_i16(), -256);
+    }
+
     #[test]
     fn test_data_ranges() {
         // Encode functions
-        assert!(FIPSCode::encode_state(SEVEN_BIT_MASK).is_ok());
-        assert!(FIPSCode::encode_state(SEVEN_BIT_MASK + 1).is_err());
+        assert!(StateCode::new(SEVEN_BIT_MASK).is_some());
+        assert!(StateCode::new(SEVEN_BIT_MASK + 1).is_none());
+        assert!(StateCode::new(0).is_none());
         assert!(FIPSCode::encode_county(TEN_BIT_MASK).is_ok());
         assert!(FIPSCode::encode_county(TEN_BIT_MASK + 1).is_err());
         assert!(FIPSCode::encode_tract(TWENTY_BIT_MASK).is_ok());
@@ -473,15 +1180,14 @@ mod tests {
         assert!(FIPSCode::encode_data(NINE_BIT_MASK).is_ok());
         assert!(FIPSCode::encode_data(NINE_BIT_MASK + 1).is_err());
         // Constructors
-        assert!(FIPSCode::with_state_code(1).is_ok());
-        assert!(FIPSCode::with_state_code(0).is_err());
-        assert!(FIPSCode::with_state_code(SEVEN_BIT_MASK + 1).is_err());
-        assert!(FIPSCode::with_county(1, 0).is_ok());
-        assert!(FIPSCode::with_county(1, TEN_BIT_MASK + 1).is_err());
-        assert!(FIPSCode::with_tract(1, 0, 0).is_ok());
-        assert!(FIPSCode::with_tract(1, 0, TWENTY_BIT_MASK + 1).is_err());
-        assert!(FIPSCode::with_category(1, 0, 0, 0).is_ok());
-        assert!(FIPSCode::with_category(1, 0, 0, FOUR_BIT_MASK + 1).is_err());
+        let state = StateCode::new(1).unwrap();
+        assert_eq!(FIPSCode::with_state_code(state).state_code(), state);
+        assert!(FIPSCode::with_county(state, 0).is_ok());
+        assert!(FIPSCode::with_county(state, TEN_BIT_MASK + 1).is_err());
+        assert!(FIPSCode::with_tract(state, 0, 0).is_ok());
+        assert!(FIPSCode::with_tract(state, 0, TWENTY_BIT_MASK + 1).is_err());
+        assert!(FIPSCode::with_category(state, 0, 0, 0).is_ok());
+        assert!(FIPSCode::with_category(state, 0, 0, FOUR_BIT_MASK + 1).is_err());
     }
 
     #[test]
@@ -498,7 +1204,10 @@ mod tests {
         assert_eq!(fips_code.state().unwrap(), USState::TX);
         assert_eq!(fips_code.county_code(), 123);
         assert_eq!(fips_code.census_tract_code(), 990101);
-        assert_eq!(fips_code.category_code(), SettingCategory::Home.into());
+        assert_eq!(
+            fips_code.category_code(),
+            SettingCategory::Home as SettingCategoryCode
+        );
         assert_eq!(fips_code.id(), 14938);
         assert_eq!(fips_code.data(), 123);
     }
@@ -519,6 +1228,164 @@ mod tests {
         assert_eq!(result, fips_code);
     }
 
+    #[test]
+    fn new_unchecked_matches_try_new() {
+        assert_eq!(
+            FIPSCode::new_unchecked(USState::TX, 201, 223_100),
+            FIPSCode::try_new(USState::TX, 201, 223_100).unwrap()
+        );
+        assert!(FIPSCode::try_new(USState::TX, TEN_BIT_MASK + 1, 223_100).is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "county code 1024 out of range")]
+    fn new_unchecked_debug_asserts() {
+        let _ = FIPSCode::new_unchecked(USState::TX, TEN_BIT_MASK + 1, 223_100);
+    }
+
+    #[test]
+    fn expanded_out_of_range() {
+        let mut expanded = ExpandedFIPSCode::from_fips_code(
+            FIPSCode::with_county(USState::TX.into(), 201).unwrap(),
+        );
+        expanded.county = TEN_BIT_MASK + 1;
+        assert!(expanded.to_fips_code().is_err());
+    }
+
+    #[test]
+    fn test_has_data() {
+        let fips_code = FIPSCode::new(
+            USState::TX.into(),
+            123,
+            990_101,
+            SettingCategory::Home.into(),
+            14938,
+            0,
+        )
+        .unwrap();
+        assert!(!fips_code.has_data());
+        assert!(fips_code.set_data(1).unwrap().has_data());
+        assert!(fips_code.set_data(0x01ff).unwrap().has_data());
+        assert!(!fips_code.set_data(0x01ff).unwrap().set_data(0).unwrap().has_data());
+        assert_eq!(
+            fips_code.set_data(0x01ff).unwrap().without_data(),
+            fips_code
+        );
+
+        let mut in_place = fips_code;
+        in_place.set_data_in_place(0x01ff).unwrap();
+        assert!(in_place.has_data());
+        assert_eq!(
+            in_place.set_data_in_place(0x0200),
+            Err(FIPSCodeError::FieldOverflow {
+                field: "data",
+                value: 0x0200,
+                max: 0x01ff,
+            })
+        );
+    }
+
+    #[test]
+    fn test_c_round_trip() {
+        let fips_code = FIPSCode::new(
+            USState::TX.into(),
+            123,
+            990_101,
+            SettingCategory::Work.into(),
+            14938,
+            0x01ff,
+        )
+        .unwrap();
+        let fips_code_c = fips_code.to_c();
+        assert_eq!(
+            fips_code_c,
+            FIPSCodeC {
+                state: 48,
+                county: 123,
+                tract: 990_101,
+                category: SettingCategory::Work.into(),
+                id: 14938,
+                data: 0x01ff,
+            }
+        );
+        assert_eq!(FIPSCode::from_c(fips_code_c), Ok(fips_code));
+
+        let out_of_range = FIPSCodeC {
+            county: 1024,
+            ..fips_code_c
+        };
+        assert!(FIPSCode::from_c(out_of_range).is_err());
+    }
+
+    #[test]
+    fn test_from_u64_slice() {
+        let tx = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let wy = FIPSCode::new(USState::WY.into(), 1, 100, 2, 37, 5).unwrap();
+        // A nonzero encoding whose state field is zero.
+        let no_state = wy.as_u64() & !(SEVEN_BIT_MASK as u64) << STATE_OFFSET;
+
+        let results = FIPSCode::from_u64_slice(&[tx.as_u64(), 0, wy.as_u64(), no_state]);
+        assert_eq!(
+            results,
+            vec![
+                Ok(tx),
+                Err(FIPSCodeError::InvalidStateCode { value: 0 }),
+                Ok(wy),
+                Err(FIPSCodeError::InvalidStateCode { value: 0 }),
+            ]
+        );
+
+        assert_eq!(
+            FIPSCode::try_from_u64_slice(&[tx.as_u64(), wy.as_u64()]),
+            Ok(vec![tx, wy])
+        );
+        assert_eq!(
+            FIPSCode::try_from_u64_slice(&[tx.as_u64(), no_state, 0]),
+            Err((1, FIPSCodeError::InvalidStateCode { value: 0 }))
+        );
+        assert_eq!(FIPSCode::try_from_u64_slice(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_error_variants() {
+        match FIPSCode::new(USState::TX.into(), 1024, 0, 0, 0, 0) {
+            Err(FIPSCodeError::FieldOverflow { field, value, max }) => {
+                assert_eq!(field, "county");
+                assert_eq!(value, 1024);
+                assert_eq!(max, 1023);
+            }
+            other => panic!("expected a county overflow, got {:?}", other),
+        }
+        assert_eq!(
+            FIPSCode::with_tract(USState::TX.into(), 201, 1_048_576),
+            Err(FIPSCodeError::FieldOverflow {
+                field: "tract",
+                value: 1_048_576,
+                max: 1_048_575,
+            })
+        );
+
+        let fips_code_c = FIPSCode::with_state(USState::TX).to_c();
+        match FIPSCode::from_c(FIPSCodeC {
+            state: 0,
+            ..fips_code_c
+        }) {
+            Err(FIPSCodeError::InvalidStateCode { value }) => assert_eq!(value, 0),
+            other => panic!("expected an invalid state code, got {:?}", other),
+        }
+        // The unassigned state code 3 fits in a `StateCode` but is not the code of a `USState`.
+        let unassigned = FIPSCode::with_state_code(StateCode::new(3).unwrap());
+        assert_eq!(
+            unassigned.state(),
+            Err(FIPSCodeError::InvalidStateCode { value: 3 })
+        );
+        assert_eq!(
+            unassigned.state().unwrap_err().to_string(),
+            "3 is not a valid state code"
+        );
+    }
+
     #[test]
     fn test_compare_non_data() {
         let fips_code_a = FIPSCode::new(
@@ -544,6 +1411,543 @@ mod tests {
         assert_eq!(fips_code_a.cmp(&fips_code_b), Ordering::Greater);
     }
 
+    #[test]
+    fn test_same_location() {
+        let home = FIPSCode::new(
+            USState::TX.into(),
+            201,
+            223_100,
+            SettingCategory::Home.into(),
+            24,
+            0,
+        )
+        .unwrap();
+        let workplace = FIPSCode::new(
+            USState::TX.into(),
+            201,
+            223_100,
+            SettingCategory::Work.into(),
+            1546,
+            0x00ff,
+        )
+        .unwrap();
+        let other_tract = home.set_tract(223_200).unwrap();
+
+        assert!(home.same_location(workplace));
+        assert!(workplace.same_location(home));
+        assert!(!home.same_location(other_tract));
+    }
+
+    #[test]
+    fn test_is_complete() {
+        let state = FIPSCode::with_state(USState::TX);
+        let county = FIPSCode::with_county(USState::TX.into(), 201).unwrap();
+        let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let home = tract.set_category(SettingCategory::Home.into()).unwrap();
+        let tract_without_county = FIPSCode::with_tract(USState::TX.into(), 0, 223_100).unwrap();
+
+        assert!(!state.is_complete_county());
+        assert!(!state.is_complete_tract());
+        assert!(county.is_complete_county());
+        assert!(!county.is_complete_tract());
+        assert!(tract.is_complete_county());
+        assert!(tract.is_complete_tract());
+        assert!(home.is_complete_tract());
+        assert!(!tract_without_county.is_complete_county());
+        assert!(!tract_without_county.is_complete_tract());
+    }
+
+    #[test]
+    fn test_without_category() {
+        let fips_code = FIPSCode::new(
+            USState::TX.into(),
+            123,
+            990_101,
+            SettingCategory::Work.into(),
+            14938,
+            0x01ff,
+        )
+        .unwrap();
+        let cleared = fips_code.without_category();
+
+        assert_eq!(cleared.category_code(), 0);
+        assert_eq!(cleared.state_code(), fips_code.state_code());
+        assert_eq!(cleared.county_code(), fips_code.county_code());
+        assert_eq!(cleared.census_tract_code(), fips_code.census_tract_code());
+        assert_eq!(cleared.id(), fips_code.id());
+        assert_eq!(cleared.data(), fips_code.data());
+        assert_eq!(cleared, fips_code.set_category(0).unwrap());
+        assert_eq!(cleared.without_category(), cleared);
+    }
+
+    #[test]
+    fn test_same_county() {
+        let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let same_county = FIPSCode::with_tract(USState::TX.into(), 201, 223_200).unwrap();
+        let other_county = FIPSCode::with_tract(USState::TX.into(), 203, 223_100).unwrap();
+        let other_state = FIPSCode::with_tract(USState::OK.into(), 201, 223_100).unwrap();
+
+        assert!(tract.same_county(same_county));
+        assert!(tract.same_county(same_county.set_category(1).unwrap()));
+        assert!(!tract.same_county(other_county));
+        assert!(!tract.same_county(other_state));
+    }
+
+    #[test]
+    fn test_county_sort_key() {
+        let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let same_county = FIPSCode::with_tract(USState::TX.into(), 201, 223_200).unwrap();
+        assert_eq!(tract.county_sort_key(), same_county.county_sort_key());
+        assert_eq!(
+            tract.county_sort_key(),
+            tract.set_id(12).unwrap().county_sort_key()
+        );
+        assert_eq!(tract.county_sort_key(), 48 * 1024 + 201);
+
+        let mut codes = [
+            FIPSCode::with_county(USState::TX.into(), 1).unwrap(),
+            FIPSCode::with_tract(USState::AL.into(), 1023, 100).unwrap(),
+            tract,
+            FIPSCode::with_county(USState::WY.into(), 1).unwrap(),
+            FIPSCode::with_county(USState::AL.into(), 3).unwrap(),
+        ];
+        codes.sort_by_key(FIPSCode::county_sort_key);
+        let keys: Vec<(u8, CountyCode)> = codes
+            .iter()
+            .map(|code| (code.state_code().get(), code.county_code()))
+            .collect();
+        assert_eq!(keys, vec![(1, 3), (1, 1023), (48, 1), (48, 201), (56, 1)]);
+    }
+
+    #[test]
+    fn test_parent_codes() {
+        let fips_code = FIPSCode::new(
+            USState::TX.into(),
+            201,
+            223_100,
+            SettingCategory::Home.into(),
+            24,
+            0x01ff,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fips_code.parent_tract(),
+            FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap()
+        );
+        assert_eq!(
+            fips_code.parent_county(),
+            FIPSCode::with_county(USState::TX.into(), 201).unwrap()
+        );
+        assert_eq!(fips_code.parent_state(), FIPSCode::with_state(USState::TX));
+
+        // Truncating is idempotent.
+        let tract = fips_code.parent_tract();
+        assert_eq!(tract.parent_tract(), tract);
+        assert_eq!(tract.parent_county(), fips_code.parent_county());
+    }
+
+    #[test]
+    fn test_prefix_bounds() {
+        let county = FIPSCode::with_county(USState::TX.into(), 201).unwrap();
+        let (min, max) = county.prefix_bounds();
+
+        let inside = FIPSCode::new(
+            USState::TX.into(),
+            201,
+            223_100,
+            SettingCategory::Home.into(),
+            24,
+            0x01ff,
+        )
+        .unwrap();
+        assert!((min..=max).contains(&inside.as_u64()));
+        assert!((min..=max).contains(&county.as_u64()));
+
+        let outside = inside.set_county(203).unwrap();
+        assert!(!(min..=max).contains(&outside.as_u64()));
+        let next_county = FIPSCode::with_county(USState::TX.into(), 202).unwrap();
+        assert_eq!(max + 1, next_county.as_u64());
+
+        // A tract whose code has trailing zero bits still bounds only that tract.
+        let tract = inside.parent_tract();
+        let (min, max) = tract.prefix_bounds();
+        assert!((min..=max).contains(&inside.as_u64()));
+        assert!(!(min..=max).contains(&inside.set_tract(223_101).unwrap().as_u64()));
+
+        // A fully specified code bounds only itself.
+        assert_eq!(inside.prefix_bounds(), (inside.as_u64(), inside.as_u64()));
+    }
+
+    #[test]
+    fn test_fields_from_u64() {
+        let fips_code = FIPSCode::new(
+            USState::TX.into(),
+            201,
+            223_100,
+            SettingCategory::Work.into(),
+            14938,
+            0x01ff,
+        )
+        .unwrap();
+        let bits = fips_code.0.get();
+
+        assert_eq!(state_code_from_u64(bits), USState::TX.encode().get());
+        assert_eq!(county_code_from_u64(bits), 201);
+        assert_eq!(tract_code_from_u64(bits), 223_100);
+
+        let fips_code = FIPSCode::with_state(USState::WY);
+        let bits = fips_code.0.get();
+
+        assert_eq!(state_code_from_u64(bits), USState::WY.encode().get());
+        assert_eq!(county_code_from_u64(bits), 0);
+        assert_eq!(tract_code_from_u64(bits), 0);
+    }
+
+    #[test]
+    fn test_typed_data() {
+        #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+        enum VaccinationStatus {
+            Unvaccinated,
+            PartiallyVaccinated,
+            Vaccinated,
+        }
+
+        impl From<VaccinationStatus> for DataCode {
+            fn from(value: VaccinationStatus) -> Self {
+                value as DataCode
+            }
+        }
+
+        impl TryFrom<DataCode> for VaccinationStatus {
+            type Error = ();
+
+            fn try_from(value: DataCode) -> Result<Self, ()> {
+                match value {
+                    0 => Ok(VaccinationStatus::Unvaccinated),
+                    1 => Ok(VaccinationStatus::PartiallyVaccinated),
+                    2 => Ok(VaccinationStatus::Vaccinated),
+                    _ => Err(()),
+                }
+            }
+        }
+
+        let fips_code = FIPSCode::new(
+            USState::TX.into(),
+            201,
+            223_100,
+            SettingCategory::Home.into(),
+            24,
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            fips_code.data_typed::<VaccinationStatus>(),
+            Ok(VaccinationStatus::Unvaccinated)
+        );
+
+        for status in [
+            VaccinationStatus::Unvaccinated,
+            VaccinationStatus::PartiallyVaccinated,
+            VaccinationStatus::Vaccinated,
+        ] {
+            let tagged = fips_code.set_data_typed(status).unwrap();
+            assert_eq!(tagged.data_typed::<VaccinationStatus>(), Ok(status));
+            // The other fields are untouched.
+            assert_eq!(tagged.compare_non_data(fips_code), Ordering::Equal);
+        }
+
+        // A data region that doesn't hold a valid value
+        let invalid = fips_code.set_data(7).unwrap();
+        assert_eq!(
+            invalid.data_typed::<VaccinationStatus>(),
+            Err(FIPSCodeError::InvalidData { value: 7 })
+        );
+        // A value that doesn't fit in the data region
+        assert!(fips_code.set_data_typed(NINE_BIT_MASK + 1).is_err());
+    }
+
+    #[test]
+    fn test_describe() {
+        let fips_code = FIPSCode::new(
+            USState::TX.into(),
+            201,
+            223_100,
+            SettingCategory::Home.into(),
+            24,
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            fips_code.describe(),
+            "Texas, County 201, Tract 2231 (Setting 1 #24)"
+        );
+        assert_eq!(
+            fips_code
+                .parent_tract()
+                .set_tract(950_101)
+                .unwrap()
+                .describe(),
+            "Texas, County 201, Tract 9501.01"
+        );
+        assert_eq!(fips_code.parent_state().describe(), "Texas");
+        assert_eq!(
+            FIPSCode::with_county(StateCode::new(72).unwrap(), 1)
+                .unwrap()
+                .describe(),
+            "State 72, County 001"
+        );
+    }
+
+    #[test]
+    fn test_to_census_api_params() {
+        let home = FIPSCode::new(USState::AL.into(), 1, 20_100, 1, 24, 0).unwrap();
+        let params = home.to_census_api_params();
+        assert_eq!(
+            params,
+            vec![
+                ("state", "01".to_string()),
+                ("county", "001".to_string()),
+                ("tract", "020100".to_string()),
+            ]
+        );
+        let query = params
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        assert_eq!(query, "state=01&county=001&tract=020100");
+
+        let county = FIPSCode::with_county(USState::TX.into(), 201).unwrap();
+        assert_eq!(
+            county.to_census_api_params(),
+            vec![("state", "48".to_string()), ("county", "201".to_string())]
+        );
+        assert_eq!(
+            FIPSCode::with_state(USState::WY).to_census_api_params(),
+            vec![("state", "56".to_string())]
+        );
+    }
+
+    #[cfg(feature = "geojson")]
+    #[test]
+    fn test_to_property_map() {
+        use serde_json::json;
+
+        let home = FIPSCode::new(USState::TX.into(), 201, 223_100, 1, 24, 0).unwrap();
+        let properties = home.to_property_map();
+        assert_eq!(
+            properties.keys().copied().collect::<Vec<_>>(),
+            vec!["GEOID", "category", "county", "state", "tract"]
+        );
+        assert_eq!(properties["GEOID"], json!("48201223100"));
+        assert_eq!(properties["state"], json!("48"));
+        assert_eq!(properties["county"], json!("201"));
+        assert_eq!(properties["tract"], json!("223100"));
+        assert_eq!(properties["category"], json!(1));
+
+        let county = FIPSCode::with_county(USState::AL.into(), 1)
+            .unwrap()
+            .to_property_map();
+        assert_eq!(county["GEOID"], json!("01001"));
+        assert!(!county.contains_key("tract"));
+    }
+
+    #[test]
+    fn test_breadcrumb() {
+        let fips_code = FIPSCode::new(
+            USState::TX.into(),
+            201,
+            223_100,
+            SettingCategory::Home.into(),
+            24,
+            0,
+        )
+        .unwrap();
+        let breadcrumb = fips_code.breadcrumb();
+        assert_eq!(breadcrumb.len(), 3);
+        assert_eq!(
+            breadcrumb,
+            vec![
+                (GeographicLevel::State, "TX".to_string()),
+                (GeographicLevel::County, "201".to_string()),
+                (GeographicLevel::Tract, "2231".to_string()),
+            ]
+        );
+        let label = breadcrumb
+            .iter()
+            .map(|(level, name)| format!("{level}: {name}"))
+            .collect::<Vec<_>>()
+            .join(" > ");
+        assert_eq!(label, "State: TX > County: 201 > Tract: 2231");
+
+        assert_eq!(
+            fips_code.parent_county().breadcrumb(),
+            vec![
+                (GeographicLevel::State, "TX".to_string()),
+                (GeographicLevel::County, "201".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_id_range() {
+        let fips_code = FIPSCode::with_category(
+            USState::TX.into(),
+            201,
+            223_100,
+            SettingCategory::Home.into(),
+        )
+        .unwrap();
+
+        let codes: Vec<FIPSCode> = fips_code.id_range(5).unwrap().collect();
+        assert_eq!(codes.len(), 5);
+        for (idx, code) in codes.iter().enumerate() {
+            assert_eq!(code.id() as usize, idx + 1);
+            assert_eq!(code.set_id(0).unwrap(), fips_code);
+        }
+        // The codes are distinct.
+        assert!(codes.windows(2).all(|pair| pair[0] < pair[1]));
+
+        assert_eq!(fips_code.id_range(0).unwrap().count(), 0);
+        assert!(fips_code.id_range(FOURTEEN_BIT_MASK).is_ok());
+        assert!(fips_code.id_range(FOURTEEN_BIT_MASK + 1).is_err());
+    }
+
+    #[test]
+    fn test_fields() {
+        assert_eq!(FIELDS.iter().map(|field| field.bits).sum::<usize>(), 64);
+
+        // The fields are contiguous, from the most significant bit down to the least.
+        assert_eq!(FIELDS[0].offset + FIELDS[0].bits, 64);
+        for pair in FIELDS.windows(2) {
+            assert_eq!(pair[1].offset + pair[1].bits, pair[0].offset);
+        }
+        assert_eq!(FIELDS[FIELDS.len() - 1].offset, 0);
+
+        let masks = [
+            u64::from(SEVEN_BIT_MASK),
+            u64::from(TEN_BIT_MASK),
+            u64::from(TWENTY_BIT_MASK),
+            u64::from(FOUR_BIT_MASK),
+            u64::from(FOURTEEN_BIT_MASK),
+            u64::from(NINE_BIT_MASK),
+        ];
+        let offsets = [
+            STATE_OFFSET,
+            COUNTY_OFFSET,
+            TRACT_OFFSET,
+            CATEGORY_OFFSET,
+            ID_OFFSET,
+            0,
+        ];
+        for ((field, mask), offset) in FIELDS.iter().zip(masks).zip(offsets) {
+            assert_eq!((1 << field.bits) - 1, mask, "{}", field.name);
+            assert_eq!(field.offset, offset, "{}", field.name);
+        }
+    }
+
+    #[test]
+    fn test_geo_shard() {
+        let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        let home = tract
+            .set_category(SettingCategory::Home.into())
+            .unwrap()
+            .set_id(24)
+            .unwrap();
+        let workplace = tract
+            .set_category(SettingCategory::Work.into())
+            .unwrap()
+            .set_id(9_999)
+            .unwrap()
+            .set_data(7)
+            .unwrap();
+        for num_shards in [1, 2, 7, 64, 1_000] {
+            let shard = tract.geo_shard(num_shards);
+            assert!(shard < num_shards);
+            assert_eq!(home.geo_shard(num_shards), shard);
+            assert_eq!(workplace.geo_shard(num_shards), shard);
+        }
+
+        // The assignment is a fixed function of the tract, so it must not change between releases.
+        assert_eq!(tract.geo_shard(1_000), 506);
+
+        // Neighboring tracts are spread across shards.
+        let shards: std::collections::HashSet<u32> = (1..=100)
+            .map(|tract_code| tract.set_tract(tract_code * 100).unwrap().geo_shard(8))
+            .collect();
+        assert_eq!(shards.len(), 8);
+    }
+
+    #[test]
+    fn test_tracts_in_county() {
+        let tracts = [223_100, 223_200, 950_101];
+        let codes: Vec<FIPSCode> = FIPSCode::tracts_in_county(USState::TX.into(), 201, &tracts)
+            .unwrap()
+            .collect();
+        let expected: Vec<FIPSCode> = tracts
+            .iter()
+            .map(|&tract| FIPSCode::with_tract(USState::TX.into(), 201, tract).unwrap())
+            .collect();
+        assert_eq!(codes, expected);
+
+        assert_eq!(
+            FIPSCode::tracts_in_county(USState::TX.into(), 201, &[])
+                .unwrap()
+                .count(),
+            0
+        );
+        assert!(FIPSCode::tracts_in_county(USState::TX.into(), 1024, &tracts).is_err());
+        let out_of_range = [223_100, 1_048_576];
+        assert!(FIPSCode::tracts_in_county(USState::TX.into(), 201, &out_of_range).is_err());
+    }
+
+    #[test]
+    fn test_counties_in_state() {
+        let counties = [1, 3, 507];
+        let codes: Vec<FIPSCode> = FIPSCode::counties_in_state(USState::TX.into(), &counties)
+            .unwrap()
+            .collect();
+        let expected: Vec<FIPSCode> = counties
+            .iter()
+            .map(|&county| FIPSCode::with_county(USState::TX.into(), county).unwrap())
+            .collect();
+        assert_eq!(codes, expected);
+        assert!(codes.iter().all(FIPSCode::is_complete_county));
+
+        assert_eq!(
+            FIPSCode::counties_in_state(USState::TX.into(), &[])
+                .unwrap()
+                .count(),
+            0
+        );
+        assert!(FIPSCode::counties_in_state(USState::TX.into(), &[1, 1024]).is_err());
+    }
+
+    #[test]
+    fn test_slash_path() {
+        let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        assert_eq!(tract.slash_path(), "TX/201/223100");
+
+        let home = FIPSCode::new(
+            USState::AL.into(),
+            1,
+            20100,
+            SettingCategory::Home.into(),
+            24,
+            0,
+        )
+        .unwrap();
+        assert_eq!(home.slash_path(), "AL/001/020100/1/24");
+
+        assert_eq!(FIPSCode::with_state(USState::WY).slash_path(), "WY");
+        let puerto_rico = StateCode::new(72).unwrap();
+        assert_eq!(
+            FIPSCode::with_county(puerto_rico, 1).unwrap().slash_path(),
+            "72/001"
+        );
+    }
+
     #[test]
     fn test_set_id() {
         // Exercises case that triggered a bug that causes a panic.
@@ -558,4 +1962,27 @@ mod tests {
         let other_fips_code = fips_code.set_id(0).unwrap();
         assert_eq!(fips_code, other_fips_code);
     }
+
+    #[test]
+    fn test_map_id_and_data() {
+        let fips_code = FIPSCode::new(USState::TX.into(), 201, 223_100, 1, 24, 3).unwrap();
+
+        let next = fips_code.map_id(|id| id + 1).unwrap();
+        assert_eq!(next.id(), 25);
+        assert!(next.same_location(fips_code));
+        assert_eq!(next.data(), 3);
+        assert_eq!(
+            fips_code.map_id(|_| 16_384),
+            Err(FIPSCodeError::FieldOverflow {
+                field: "id",
+                value: 16_384,
+                max: 16_383,
+            })
+        );
+
+        let doubled = fips_code.map_data(|data| data * 2).unwrap();
+        assert_eq!(doubled.data(), 6);
+        assert_eq!(doubled.id(), 24);
+        assert!(fips_code.map_data(|_| 512).is_err());
+    }
 }
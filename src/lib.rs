@@ -5,11 +5,46 @@
 //! workplaces, and schools. This library provides types to represent FIPS geographic region codes (and "code fragments"),
 //! efficient representations, and utilities to convert to and from textual representations ([`crate::parser`]).
 //!
+//! The parsers of code fragments, and their result and error types, are re-exported at the crate root:
+//!
+//! ```
+//! use ixa_fips::{parse_county_code, parse_state_code, parse_tract_code, FIPSCode, FIPSParseResult};
+//!
+//! fn parse_tract(input: &str) -> FIPSParseResult<FIPSCode> {
+//!     let (rest, state) = parse_state_code(input)?;
+//!     let (rest, county) = parse_county_code(rest)?;
+//!     let (rest, tract) = parse_tract_code(rest)?;
+//!     let code = FIPSCode::with_tract(state, county, tract).expect("parsed fragments are in range");
+//!     Ok((rest, code))
+//! }
+//!
+//! let (_, tract) = parse_tract("48201223100").unwrap();
+//! assert_eq!(tract.census_tract_code(), 223_100);
+//! ```
+//!
 //! The [`crate::aspr`] module provides types for representing records from the ASPR synthetic population dataset, and the
 //! [`crate::aspr::parser`] submodule provides parsers for textual representations of ASPR records.
 //!
 //! The `aspr_archive` feature (enabled by default) enables the [`crate::aspr::archive`] module, which provides a reader
 //! for ASPR synthetic population data files, including files that are within a zip archive.
+//!
+//! The `tract_validation` feature enables the [`crate::tract_validation`] module, which checks codes against a
+//! user-supplied list of census tracts that actually exist.
+//!
+//! The `urban_rural` feature enables the [`crate::urban_rural`] module, which classifies census tracts as urban or
+//! rural from a user-supplied tract list.
+//!
+//! The `zcta_crosswalk` feature enables the [`crate::zcta_crosswalk`] module, which relates ZIP Code Tabulation Areas
+//! to the counties they overlap, from a user-supplied relationship table.
+//!
+//! The [`crate::code_index`] module persists a sorted set of codes to a file for fast membership tests. With the
+//! `memmap2` feature, index files are memory-mapped rather than read into memory.
+//!
+//! The `fast_hash` feature enables [`crate::collections::FIPSMap`], a `HashMap` keyed by `FIPSCode` with a hasher much
+//! cheaper than the default one.
+//!
+//! The `geojson` feature enables `FIPSCode::to_property_map`, which gives the properties of a code as JSON values, e.g.
+//! for the features of a map.
 
 #![allow(dead_code)]
 // Positive instances of the following lints have been audited.
@@ -17,12 +52,30 @@
 #![allow(clippy::cast_possible_truncation)]
 #![allow(clippy::cast_lossless)]
 
+pub mod code_index;
+pub mod collections;
 pub mod fips_code;
 pub mod parser;
+pub mod place_code;
 pub mod states;
+#[cfg(feature = "tract_validation")]
+pub mod tract_validation;
+#[cfg(feature = "urban_rural")]
+pub mod urban_rural;
+pub mod zcta;
+#[cfg(feature = "zcta_crosswalk")]
+pub mod zcta_crosswalk;
 
-pub use fips_code::{ExpandedFIPSCode, FIPSCode};
-pub use states::USState;
+pub use fips_code::{
+    county_code_from_u64, state_code_from_u64, tract_code_from_u64, ExpandedFIPSCode, FIPSCode,
+    FIPSCodeC, FIPSCodeError, FieldSpec, GeographicLevel, FIELDS,
+};
+pub use parser::{
+    parse_bare_tract_code, parse_census_api_geoid, parse_county_code, parse_place_code,
+    parse_state_code, parse_tract_code, parse_zcta_code, FIPSParseResult, FIPSParserError,
+};
+pub use place_code::PlaceCode;
+pub use states::{StateOrLegacy, StateSet, USState, VALID_STATE_CODES};
 
 // Convenience constants
 const FOUR_BIT_MASK: u8 = 15; // 2^4-1
@@ -42,8 +95,46 @@ const ID_OFFSET: usize = 9;
 
 // Numeric types used for code fragments. By convention, zero values are reserved for "no data."
 
-/// The numeric type used for the state code fragment; `u8`
-pub type StateCode = u8;
+/// The type used for the state code fragment: a `u8` that is validated to be nonzero and representable in the 7 bits
+/// allotted to it by `FIPSCode`. The codes of outlying areas, e.g. 72 for Puerto Rico, are valid state codes even
+/// though `USState` does not represent them. Convert to and from `USState` with `From` and `TryFrom`.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[repr(transparent)]
+pub struct StateCode(u8);
+
+impl StateCode {
+    /// Returns the state code `value`, or `None` if `value` is zero or does not fit in 7 bits.
+    #[inline(always)]
+    #[must_use]
+    pub const fn new(value: u8) -> Option<Self> {
+        if value != 0 && value <= SEVEN_BIT_MASK {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the numeric value of the state code.
+    #[inline(always)]
+    #[must_use]
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<StateCode> for u8 {
+    fn from(state_code: StateCode) -> Self {
+        state_code.get()
+    }
+}
+
+impl std::fmt::Display for StateCode {
+    /// Formats the numeric value, respecting width and fill, so `format!("{:02}", code)` gives the 2-digit GEOID form.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
 /// The numeric type used for the county code fragment; `u16`
 pub type CountyCode = u16;
 /// The numeric type used for the tract code fragment; `u32`
@@ -32,28 +32,50 @@
 //! \** ZIP Code Tabulation Areas (ZCTAs) are generalized areal representations
 //! of United States Postal Service (USPS) ZIP Code service areas.
 
-use crate::StateCode;
+use crate::{
+    place_code::PlaceCode, zcta::ZctaCode, CountyCode, FIPSCode, GeographicLevel, StateCode,
+    TractCode,
+};
 use std::fmt::{Debug, Display};
 
 /// The FIPS parser error type.
 /// The assumption is that the parsing context is so small that it isn't necessary to track source location information.
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum FIPSParserError {
+    /// The input is empty or consists only of whitespace, e.g. an absent id field.
+    Empty,
     InvalidDigit { found: char },
     InvalidLength { expected: u32, found: u32 },
+    /// The value of a field that must be nonzero, such as a state code, is zero.
+    Zero,
+    /// The length of the input fits more than one kind of code, so the kind cannot be inferred from the input alone.
+    Ambiguous { found: u32 },
     ValueExceedsCapacity { value: u64, capacity: u64 },
+    /// The `found` digits of the input denote a value too large to be represented by a `u64`.
+    Overflow { found: u32 },
+    /// The input does not start with a recognized prefix, such as a supported Census summary level.
+    UnknownPrefix,
 }
 
 impl Display for FIPSParserError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            FIPSParserError::Empty => write!(f, "Empty input"),
             FIPSParserError::InvalidDigit { found } => write!(f, "Invalid digit: {}", found),
             FIPSParserError::InvalidLength { expected, found } => {
                 write!(f, "Expected {} characters, found {}", expected, found)
             }
+            FIPSParserError::Zero => write!(f, "Zero value in a field that must be nonzero"),
+            FIPSParserError::Ambiguous { found } => {
+                write!(f, "Ambiguous input: {} characters fit more than one kind of code", found)
+            }
             FIPSParserError::ValueExceedsCapacity { value, capacity } => {
                 write!(f, "Value {} exceeds max capacity {}", value, capacity)
             }
+            FIPSParserError::Overflow { found } => {
+                write!(f, "Value of {} digits overflows a 64-bit integer", found)
+            }
+            FIPSParserError::UnknownPrefix => write!(f, "Unrecognized prefix"),
         }
     }
 }
@@ -74,6 +96,19 @@ impl std::error::Error for FIPSParserError {}
 pub type IResult<I, O, E = (I, FIPSParserError)> = Result<(I, O), E>;
 pub type FIPSParseResult<'a, T> = IResult<&'a str, T>;
 
+/// Returns the number of bytes consumed by a parser that was given `original` and returned `rest`. Every parser in this
+/// module returns the unparsed remainder of its input as a suffix of that input, so the consumed span is
+/// `original[..consumed_len(original, rest)]`.
+#[inline]
+#[must_use]
+pub fn consumed_len(original: &str, rest: &str) -> usize {
+    debug_assert!(
+        original.ends_with(rest),
+        "`rest` must be a suffix of `original`"
+    );
+    original.len() - rest.len()
+}
+
 /// A function that parses a specified number of decimal digits, enforcing the
 /// constraint that the parsed value of those digits be representable by the
 /// specified number of binary bits. Upon success, returns the remainder of the
@@ -81,13 +116,16 @@ pub type FIPSParseResult<'a, T> = IResult<&'a str, T>;
 /// parsed digits. If there is an error, the original input is returned along
 /// with the `FIPSParserError` variant describing the error.
 ///
-/// This function assumes ASCII decimal digits. (The rest of the string can be any valid UTF-8.)
+/// This function assumes ASCII decimal digits. (The rest of the string can be any valid UTF-8.) A `bit_count` of 64 or
+/// more allows any `u64`, and digits whose value does not fit in a `u64` give `FIPSParserError::Overflow`.
 pub fn parse_decimal_digits_to_bits(
     digit_count: u32,
     bit_count: u8,
     input: &str,
 ) -> IResult<&str, u64> {
-    let maximum_allowed_value = (1u64 << bit_count) - 1;
+    let maximum_allowed_value = 1u64
+        .checked_shl(bit_count.into())
+        .map_or(u64::MAX, |bound| bound - 1);
     let mut input_bytes = input.as_bytes().iter();
     let mut computed_value: u64 = 0;
 
@@ -95,7 +133,15 @@ pub fn parse_decimal_digits_to_bits(
         match input_bytes.next() {
             Some(c) => {
                 if c.is_ascii_digit() {
-                    computed_value = 10 * computed_value + (c - b'0') as u64;
+                    computed_value = match computed_value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add((c - b'0') as u64))
+                    {
+                        Some(value) => value,
+                        None => {
+                            return Err((input, FIPSParserError::Overflow { found: digit_count }));
+                        }
+                    };
                 } else {
                     return Err((
                         input,
@@ -137,27 +183,217 @@ pub fn parse_decimal_digits_to_bits(
     Ok((remaining, computed_value))
 }
 
-/// Parses the first two decimal digits of `input` into a `StateCode`.
+/// Parses the first two decimal digits of `input` into a `StateCode`. Returns `FIPSParserError::Zero` for `"00"`.
 pub fn parse_state_code(input: &str) -> FIPSParseResult<StateCode> {
-    parse_decimal_digits_to_bits(2, 7, input).map(|(rest, value)| (rest, value as StateCode))
+    let (rest, value) = parse_decimal_digits_to_bits(2, 7, input)?;
+    // The `parse_decimal_digits_to_bits` function guarantees `value` fits in 7 bits.
+    match StateCode::new(value as u8) {
+        Some(state_code) => Ok((rest, state_code)),
+        None => Err((input, FIPSParserError::Zero)),
+    }
 }
 
 /// Parses the first three digits of `input` as a FIPS county code.
-pub fn parse_county_code(input: &str) -> FIPSParseResult<u16> {
+pub fn parse_county_code(input: &str) -> FIPSParseResult<CountyCode> {
     parse_decimal_digits_to_bits(3, 10, input).map(|(rest, value)| {
         // The `parse_decimal_digits_to_bits` function guarantees `value` fits in 10 bits.
-        (rest, value as u16)
+        (rest, value as CountyCode)
     })
 }
 
 /// Parses the first six digits of `input` as a FIPS census tract code.
-pub fn parse_tract_code(input: &str) -> FIPSParseResult<u32> {
+pub fn parse_tract_code(input: &str) -> FIPSParseResult<TractCode> {
     parse_decimal_digits_to_bits(6, 20, input).map(|(rest, value)| {
         // The `parse_decimal_digits_to_bits` function guarantees `value` fits in 20 bits.
-        (rest, value as u32)
+        (rest, value as TractCode)
     })
 }
 
+/// Parses the 11-digit census tract GEOID at the start of `input`, returning the remainder of the input along with the
+/// tract. Used to read reference tables keyed by tract.
+pub(crate) fn parse_tract_geoid(input: &str) -> Option<(&str, FIPSCode)> {
+    let (rest, state) = parse_state_code(input).ok()?;
+    let (rest, county) = parse_county_code(rest).ok()?;
+    let (rest, tract) = parse_tract_code(rest).ok()?;
+    // The GEOID must not run on into more digits.
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((rest, FIPSCode::with_tract(state, county, tract).ok()?))
+}
+
+/// Parses `input` as a bare census tract code: exactly six digits, without the state and county prefix, as in inputs
+/// where the prefix is implied by context. An input that runs on into more digits, such as a full 11-digit census
+/// tract GEOID, is rejected with `FIPSParserError::InvalidLength` rather than misread as a tract code. Combine the
+/// result with its state and county using `FIPSCode::with_context`.
+pub fn parse_bare_tract_code(input: &str) -> FIPSParseResult<TractCode> {
+    let (rest, tract) = parse_tract_code(input)?;
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        let found = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+        return Err((
+            input,
+            FIPSParserError::InvalidLength {
+                expected: 6,
+                found: found as u32,
+            },
+        ));
+    }
+    Ok((rest, tract))
+}
+
+/// The prefixes of GEOIDs returned by the Census Data API, each a summary level code and geographic component followed by
+/// `"US"`, with the geographic level of the GEOID that follows.
+const CENSUS_API_PREFIXES: [(&str, GeographicLevel); 3] = [
+    ("0400000US", GeographicLevel::State),
+    ("0500000US", GeographicLevel::County),
+    ("1400000US", GeographicLevel::Tract),
+];
+
+/// Parses `input` as a GEOID prefixed as by the Census Data API, e.g. `"1400000US48201223100"`, returning the code at
+/// the level implied by the prefix: `"0400000US"` for a state, `"0500000US"` for a county, and `"1400000US"` for a
+/// census tract. Returns `FIPSParserError::UnknownPrefix` for any other prefix, and `FIPSParserError::InvalidLength` if
+/// the GEOID runs on into more digits than its level has.
+pub fn parse_census_api_geoid(input: &str) -> FIPSParseResult<FIPSCode> {
+    let Some((geoid, level)) = CENSUS_API_PREFIXES
+        .iter()
+        .find_map(|&(prefix, level)| Some((input.strip_prefix(prefix)?, level)))
+    else {
+        return Err((input, FIPSParserError::UnknownPrefix));
+    };
+
+    // Each field parser guarantees its value fits in its bits, so the constructors cannot fail.
+    let (rest, state) = parse_state_code(geoid)?;
+    let (rest, fips_code, expected) = match level {
+        GeographicLevel::State => (rest, FIPSCode::with_state_code(state), 2),
+        GeographicLevel::County => {
+            let (rest, county) = parse_county_code(rest)?;
+            (rest, FIPSCode::with_county(state, county).unwrap(), 5)
+        }
+        GeographicLevel::Tract => {
+            let (rest, county) = parse_county_code(rest)?;
+            let (rest, tract) = parse_tract_code(rest)?;
+            (rest, FIPSCode::with_tract(state, county, tract).unwrap(), 11)
+        }
+    };
+
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        let found = geoid.find(|c: char| !c.is_ascii_digit()).unwrap_or(geoid.len());
+        return Err((
+            geoid,
+            FIPSParserError::InvalidLength {
+                expected,
+                found: found as u32,
+            },
+        ));
+    }
+    Ok((rest, fips_code))
+}
+
+/// Parses the first seven digits of `input` as a place GEOID, `SSPPPPP`: a state code followed by a 5-digit place
+/// code.
+pub fn parse_place_code(input: &str) -> FIPSParseResult<PlaceCode> {
+    let (rest, state) = parse_state_code(input)?;
+    let (rest, place) = parse_decimal_digits_to_bits(5, 17, rest).map_err(|(_, e)| (input, e))?;
+    // The `parse_decimal_digits_to_bits` function guarantees `place` fits in 17 bits, and 5 digits are at most 99,999.
+    let place_code = PlaceCode::new(state, place as u32).map_err(|_| {
+        (
+            input,
+            FIPSParserError::ValueExceedsCapacity {
+                value: place,
+                capacity: 99_999,
+            },
+        )
+    })?;
+    Ok((rest, place_code))
+}
+
+/// Parses the first five digits of `input` as a ZCTA code.
+pub fn parse_zcta_code(input: &str) -> FIPSParseResult<ZctaCode> {
+    let (rest, value) = parse_decimal_digits_to_bits(5, 17, input)?;
+    // Five digits are at most 99,999, which is a valid ZCTA code.
+    Ok((rest, ZctaCode::new(value as u32).unwrap()))
+}
+
+/// Returns the block group containing the census block with the 4-character block code `block_code`, e.g. `1` for
+/// block `"1050"`. Per the Census rule noted in the table above, the block group is the first digit of the block code.
+/// A one-letter suffix (e.g. `"1050A"`) is allowed. Returns `None` if `block_code` is not a block code.
+///
+/// `FIPSCode` does not encode census blocks, so this operates on the text of the block code.
+#[must_use]
+pub fn block_group_from_block(block_code: &str) -> Option<u8> {
+    let bytes = block_code.as_bytes();
+    let (digits, suffix) = bytes.split_at_checked(4)?;
+    if !digits.iter().all(u8::is_ascii_digit) || !matches!(suffix, [] | [b'A'..=b'Z']) {
+        return None;
+    }
+    Some(digits[0] - b'0')
+}
+
+// region Check Digits
+// An optional check digit can be appended to the 11-digit census tract GEOID to catch transcription errors in manual
+// pipelines. We use the Luhn (mod 10) scheme: starting from the rightmost digit of the GEOID, every other digit is
+// doubled (subtracting 9 if the result exceeds 9), and the check digit is the amount needed to bring the sum of all
+// digits up to a multiple of 10. This scheme detects every single-digit error and almost every transposition of
+// adjacent digits.
+
+/// Returns the Luhn check digit for the 11-digit census tract GEOID of `code`. Fields below the census tract are
+/// ignored.
+#[must_use]
+pub fn tract_geoid_check_digit(code: FIPSCode) -> u8 {
+    let mut digits = [0u8; 11];
+    let fields = [
+        (code.state_code().get() as u32, 0..2),
+        (code.county_code() as u32, 2..5),
+        (code.census_tract_code(), 5..11),
+    ];
+    for (mut value, range) in fields {
+        for digit in digits[range].iter_mut().rev() {
+            *digit = (value % 10) as u8;
+            value /= 10;
+        }
+    }
+    luhn_check_digit(&digits)
+}
+
+/// Returns `true` if `input` consists of exactly 12 decimal digits, an 11-digit census tract GEOID followed by its
+/// check digit as computed by [`tract_geoid_check_digit`], and the check digit is correct.
+#[must_use]
+pub fn verify_tract_geoid_with_check(input: &str) -> bool {
+    let bytes = input.as_bytes();
+    if bytes.len() != 12 || !bytes.iter().all(u8::is_ascii_digit) {
+        return false;
+    }
+    let mut digits = [0u8; 12];
+    for (digit, c) in digits.iter_mut().zip(bytes) {
+        *digit = c - b'0';
+    }
+    luhn_check_digit(&digits[..11]) == digits[11]
+}
+
+/// Computes the Luhn check digit of `digits`, each of which is a value in `0..=9`.
+fn luhn_check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(idx, &digit)| {
+            let digit = digit as u32;
+            if idx % 2 == 0 {
+                let doubled = 2 * digit;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                digit
+            }
+        })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+// endregion Check Digits
 
 #[cfg(test)]
 mod tests {
@@ -228,6 +464,20 @@ mod tests {
                 }
             ))
         ); // 2^20 = 1048576
+
+        // Values of 64 bits or more
+        assert_eq!(
+            parse_decimal_digits_to_bits(20, 64, "18446744073709551615"),
+            Ok(("", u64::MAX))
+        );
+        assert_eq!(
+            parse_decimal_digits_to_bits(20, 255, "18446744073709551616"),
+            Err((
+                "18446744073709551616",
+                FIPSParserError::Overflow { found: 20 }
+            ))
+        );
+        assert_eq!(parse_decimal_digits_to_bits(1, 0, "0"), Ok(("", 0)));
     }
 
     #[test]
@@ -253,6 +503,12 @@ mod tests {
 
         // Empty input
         assert!(parse_state_code("").is_err());
+
+        // Zero is not a state code
+        assert_eq!(
+            parse_state_code("00rest"),
+            Err(("00rest", FIPSParserError::Zero))
+        );
     }
 
     #[test]
@@ -324,6 +580,112 @@ mod tests {
         assert!(parse_tract_code("").is_err());
     }
 
+    #[test]
+    fn test_parse_bare_tract_code() {
+        let (rest, tract) = parse_bare_tract_code("223100").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            FIPSCode::with_context(USState::TX.into(), 201, tract).unwrap(),
+            FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap()
+        );
+
+        // A prefixed tract is not a bare tract.
+        assert_eq!(
+            parse_bare_tract_code("48201223100"),
+            Err((
+                "48201223100",
+                FIPSParserError::InvalidLength {
+                    expected: 6,
+                    found: 11
+                }
+            ))
+        );
+        assert!(parse_bare_tract_code("2231").is_err());
+    }
+
+    #[test]
+    fn test_parse_census_api_geoid() {
+        assert_eq!(
+            parse_census_api_geoid("1400000US48201223100"),
+            Ok((
+                "",
+                FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap()
+            ))
+        );
+        assert_eq!(
+            parse_census_api_geoid("0500000US48201"),
+            Ok(("", FIPSCode::with_county(USState::TX.into(), 201).unwrap()))
+        );
+        assert_eq!(
+            parse_census_api_geoid("0400000US56,Wyoming"),
+            Ok((",Wyoming", FIPSCode::with_state(USState::WY)))
+        );
+
+        // The prefix determines the level.
+        assert_eq!(
+            parse_census_api_geoid("0500000US48201223100"),
+            Err((
+                "48201223100",
+                FIPSParserError::InvalidLength {
+                    expected: 5,
+                    found: 11
+                }
+            ))
+        );
+        assert!(parse_census_api_geoid("1400000US48201").is_err());
+        assert_eq!(
+            parse_census_api_geoid("1500000US482012231001"),
+            Err(("1500000US482012231001", FIPSParserError::UnknownPrefix))
+        );
+        assert_eq!(
+            parse_census_api_geoid("48201223100"),
+            Err(("48201223100", FIPSParserError::UnknownPrefix))
+        );
+    }
+
+    #[test]
+    fn test_consumed_len() {
+        let input = "123456TractInfo";
+        let (rest, _) = parse_tract_code(input).unwrap();
+        assert_eq!(consumed_len(input, rest), 6);
+        assert_eq!(&input[..consumed_len(input, rest)], "123456");
+
+        let input = "48201223100";
+        let (rest, _) = parse_state_code(input).unwrap();
+        let (rest, _) = parse_county_code(rest).unwrap();
+        assert_eq!(consumed_len(input, rest), 5);
+    }
+
+    #[test]
+    fn test_block_group_from_block() {
+        assert_eq!(block_group_from_block("1050"), Some(1));
+        assert_eq!(block_group_from_block("3001"), Some(3));
+        assert_eq!(block_group_from_block("1050A"), Some(1));
+        assert_eq!(block_group_from_block("105"), None);
+        assert_eq!(block_group_from_block("10500"), None);
+        assert_eq!(block_group_from_block("1O50"), None);
+    }
+
+    #[test]
+    fn test_tract_geoid_check_digit() {
+        // Harris County, TX, Census Tract 2231: "48201223100"
+        let code = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        assert_eq!(tract_geoid_check_digit(code), 7);
+        // Fields below the tract are ignored.
+        assert_eq!(tract_geoid_check_digit(code.set_id(24).unwrap()), 7);
+
+        assert!(verify_tract_geoid_with_check("482012231007"));
+        // Corrupted check digit
+        assert!(!verify_tract_geoid_with_check("482012231008"));
+        // Corrupted GEOID digit
+        assert!(!verify_tract_geoid_with_check("482012232007"));
+        // Transposed adjacent digits
+        assert!(!verify_tract_geoid_with_check("482102231007"));
+        // Wrong length or non-digits
+        assert!(!verify_tract_geoid_with_check("48201223100"));
+        assert!(!verify_tract_geoid_with_check("48201223100x"));
+    }
+
     #[test]
     fn test_integration_fips_parsing() {
         // Test parsing a complete FIPS code (state + county + tract)
@@ -0,0 +1,116 @@
+//! Census places, i.e. incorporated places and census designated places. A place is identified within its state by a
+//! 5-digit place code, so its GEOID has the 7-digit form `SSPPPPP`, e.g. `4835000` for Houston, TX. Places do not nest
+//! within counties, so they are not represented by `FIPSCode`. Parse a place GEOID with
+//! [`parse_place_code`](crate::parser::parse_place_code).
+//!
+//! `PlaceCode`s are ordered by their numeric GEOID, i.e. by state and then by place code, so a sorted `Vec` of them can
+//! be binary searched and a `BTreeSet` of them can be range queried. They display as their zero-padded 7-digit GEOID.
+
+use crate::{FIPSCodeError, StateCode};
+use std::fmt::{Display, Formatter};
+
+/// The largest 5-digit place code.
+const MAX_PLACE: u32 = 99_999;
+
+/// A census place: a state code together with a place code. The order of the fields makes the derived ordering that
+/// of the GEOID.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+pub struct PlaceCode {
+    state: StateCode,
+    place: u32,
+}
+
+impl PlaceCode {
+    /// Returns the place with place code `place` in the state `state`. Returns `FIPSCodeError::FieldOverflow` if `place`
+    /// has more than 5 digits.
+    pub fn new(state: StateCode, place: u32) -> Result<Self, FIPSCodeError> {
+        if place > MAX_PLACE {
+            return Err(FIPSCodeError::FieldOverflow {
+                field: "place",
+                value: place.into(),
+                max: MAX_PLACE.into(),
+            });
+        }
+        Ok(Self { state, place })
+    }
+
+    /// Returns the state code of the place.
+    #[inline(always)]
+    #[must_use]
+    pub fn state_code(&self) -> StateCode {
+        self.state
+    }
+
+    /// Returns the 5-digit place code, without the state.
+    #[inline(always)]
+    #[must_use]
+    pub fn place_code(&self) -> u32 {
+        self.place
+    }
+
+    /// Returns the 7-digit GEOID of the place, `SSPPPPP`, with both parts zero padded. This is the same as its
+    /// `Display` form.
+    #[must_use]
+    pub fn to_geoid_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for PlaceCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}{:05}", self.state, self.place)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::parse_place_code, USState};
+
+    #[test]
+    fn test_houston_round_trip() {
+        let houston = PlaceCode::new(USState::TX.into(), 35_000).unwrap();
+        assert_eq!(houston.to_geoid_string(), "4835000");
+
+        let (rest, parsed) = parse_place_code("4835000").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(parsed, houston);
+        assert_eq!(parsed.to_geoid_string(), "4835000");
+    }
+
+    #[test]
+    fn test_sort_matches_numeric_order() {
+        let geoids = ["4835000", "0100820", "4804000", "1150000", "0107000"];
+        let mut places: Vec<PlaceCode> = geoids
+            .iter()
+            .map(|geoid| parse_place_code(geoid).unwrap().1)
+            .collect();
+        places.sort();
+
+        let mut numeric: Vec<u32> = geoids.iter().map(|geoid| geoid.parse().unwrap()).collect();
+        numeric.sort_unstable();
+        let sorted: Vec<u32> = places
+            .iter()
+            .map(|place| place.to_string().parse().unwrap())
+            .collect();
+        assert_eq!(sorted, numeric);
+
+        let houston = PlaceCode::new(USState::TX.into(), 35_000).unwrap();
+        assert_eq!(places.binary_search(&houston), Ok(4));
+    }
+
+    #[test]
+    fn test_zero_padding() {
+        let place = PlaceCode::new(USState::AL.into(), 820).unwrap();
+        assert_eq!(place.to_geoid_string(), "0100820");
+        assert_eq!(format!("{place}"), "0100820");
+        assert_eq!(
+            PlaceCode::new(USState::AL.into(), 100_000),
+            Err(FIPSCodeError::FieldOverflow {
+                field: "place",
+                value: 100_000,
+                max: 99_999,
+            })
+        );
+    }
+}
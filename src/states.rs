@@ -5,9 +5,28 @@
 //! Note that the `FIPSCode` encoded type only uses six bits to encode the state code, which can accommodate codes <= 63.
 //! Thus, it is best to only use `FIPSCode` for these states.
 
-use crate::StateCode;
+use crate::{fips_code::FIPSCodeError, FIPSCode, StateCode};
 use strum::AsRefStr;
 
+/// Every state code represented by `USState`, in increasing order. The gaps at 3, 7, 14, 43, and 52 are codes that were
+/// reserved for outlying areas and never assigned to a state.
+pub const VALID_STATE_CODES: &[u8] = &[
+    1, 2, 4, 5, 6, 8, 9, 10, 11, 12, 13, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34,
+    35, 36, 37, 38, 39, 40, 41, 42, 44, 45, 46, 47, 48, 49, 50, 51, 53, 54, 55, 56,
+];
+
+/// The result of `USState::from_code_with_legacy`: either a current state code or a retired one.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum StateOrLegacy {
+    State(USState),
+    /// A retired code `code` of an outlying area, with the code that replaced it, or `None` if the area no longer has a
+    /// code.
+    Legacy {
+        code: u8,
+        modern: Option<StateCode>,
+    },
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, AsRefStr)]
 pub enum USState {
     AL = 1,
@@ -65,28 +84,230 @@ pub enum USState {
 
 impl USState {
     /// Returns true if `self` is a state or District of Columbia
+    #[must_use]
     pub fn is_state(&self) -> bool {
-        USState::is_state_code(*self as StateCode)
+        USState::is_state_code(*self as u8)
     }
 
     /// Returns true if the given state code is a state or District of Columbia
-    pub fn is_state_code(value: StateCode) -> bool {
+    #[must_use]
+    pub fn is_state_code(value: u8) -> bool {
         value <= 56u8 && ![0u8, 3, 7, 14, 43, 52].contains(&value)
     }
 
+    /// Returns the full name of this state, e.g. `"Texas"` for `USState::TX`.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            USState::AL => "Alabama",
+            USState::AK => "Alaska",
+            USState::AZ => "Arizona",
+            USState::AR => "Arkansas",
+            USState::CA => "California",
+            USState::CO => "Colorado",
+            USState::CT => "Connecticut",
+            USState::DE => "Delaware",
+            USState::DC => "District of Columbia",
+            USState::FL => "Florida",
+            USState::GA => "Georgia",
+            USState::HI => "Hawaii",
+            USState::ID => "Idaho",
+            USState::IL => "Illinois",
+            USState::IN => "Indiana",
+            USState::IA => "Iowa",
+            USState::KS => "Kansas",
+            USState::KY => "Kentucky",
+            USState::LA => "Louisiana",
+            USState::ME => "Maine",
+            USState::MD => "Maryland",
+            USState::MA => "Massachusetts",
+            USState::MI => "Michigan",
+            USState::MN => "Minnesota",
+            USState::MS => "Mississippi",
+            USState::MO => "Missouri",
+            USState::MT => "Montana",
+            USState::NE => "Nebraska",
+            USState::NV => "Nevada",
+            USState::NH => "New Hampshire",
+            USState::NJ => "New Jersey",
+            USState::NM => "New Mexico",
+            USState::NY => "New York",
+            USState::NC => "North Carolina",
+            USState::ND => "North Dakota",
+            USState::OH => "Ohio",
+            USState::OK => "Oklahoma",
+            USState::OR => "Oregon",
+            USState::PA => "Pennsylvania",
+            USState::RI => "Rhode Island",
+            USState::SC => "South Carolina",
+            USState::SD => "South Dakota",
+            USState::TN => "Tennessee",
+            USState::TX => "Texas",
+            USState::UT => "Utah",
+            USState::VT => "Vermont",
+            USState::VA => "Virginia",
+            USState::WA => "Washington",
+            USState::WV => "West Virginia",
+            USState::WI => "Wisconsin",
+            USState::WY => "Wyoming",
+        }
+    }
+
+    /// Returns the IANA names of the time zones in use in this state, primary (most populous) zone first, e.g.
+    /// `["America/Chicago", "America/Denver"]` for `USState::TX`. Each zone stands for every zone with the same
+    /// current rules, so, for example, Indiana's Eastern time counties are represented by `"America/New_York"`.
+    #[must_use]
+    pub fn time_zones(&self) -> &'static [&'static str] {
+        match self {
+            USState::AL
+            | USState::AR
+            | USState::IL
+            | USState::IA
+            | USState::LA
+            | USState::MN
+            | USState::MS
+            | USState::MO
+            | USState::OK
+            | USState::WI => &["America/Chicago"],
+            USState::AK => &["America/Anchorage", "America/Adak"],
+            USState::AZ => &["America/Phoenix", "America/Denver"],
+            USState::CA | USState::WA => &["America/Los_Angeles"],
+            USState::CO | USState::MT | USState::NM | USState::UT | USState::WY => {
+                &["America/Denver"]
+            }
+            USState::CT
+            | USState::DE
+            | USState::DC
+            | USState::GA
+            | USState::ME
+            | USState::MD
+            | USState::MA
+            | USState::NH
+            | USState::NJ
+            | USState::NY
+            | USState::NC
+            | USState::OH
+            | USState::PA
+            | USState::RI
+            | USState::SC
+            | USState::VT
+            | USState::VA
+            | USState::WV => &["America/New_York"],
+            USState::FL | USState::IN | USState::KY | USState::MI => {
+                &["America/New_York", "America/Chicago"]
+            }
+            USState::HI => &["Pacific/Honolulu"],
+            USState::ID => &["America/Denver", "America/Los_Angeles"],
+            USState::KS | USState::NE | USState::ND | USState::SD | USState::TX => {
+                &["America/Chicago", "America/Denver"]
+            }
+            USState::NV | USState::OR => &["America/Los_Angeles", "America/Denver"],
+            USState::TN => &["America/Chicago", "America/New_York"],
+        }
+    }
+
+    /// Returns the number of counties and county equivalents (e.g. Louisiana's parishes, Virginia's independent cities,
+    /// and the District of Columbia itself) in this state as of the 2020 Census, e.g. 254 for `USState::TX`. Note that
+    /// county codes are not dense (they are usually odd), so a valid county code may exceed this count.
+    #[must_use]
+    pub fn county_count(&self) -> u16 {
+        match self {
+            USState::AL | USState::FL | USState::PA => 67,
+            USState::AK => 30,
+            USState::AZ => 15,
+            USState::AR => 75,
+            USState::CA => 58,
+            USState::CO | USState::LA => 64,
+            USState::CT => 8,
+            USState::DE => 3,
+            USState::DC => 1,
+            USState::GA => 159,
+            USState::HI | USState::RI => 5,
+            USState::ID => 44,
+            USState::IL => 102,
+            USState::IN => 92,
+            USState::IA => 99,
+            USState::KS => 105,
+            USState::KY => 120,
+            USState::ME => 16,
+            USState::MD => 24,
+            USState::MA | USState::VT => 14,
+            USState::MI => 83,
+            USState::MN => 87,
+            USState::MS => 82,
+            USState::MO => 115,
+            USState::MT => 56,
+            USState::NE => 93,
+            USState::NV => 17,
+            USState::NH => 10,
+            USState::NJ => 21,
+            USState::NM => 33,
+            USState::NY => 62,
+            USState::NC => 100,
+            USState::ND => 53,
+            USState::OH => 88,
+            USState::OK => 77,
+            USState::OR => 36,
+            USState::SC => 46,
+            USState::SD => 66,
+            USState::TN => 95,
+            USState::TX => 254,
+            USState::UT => 29,
+            USState::VA => 133,
+            USState::WA => 39,
+            USState::WV => 55,
+            USState::WI => 72,
+            USState::WY => 23,
+        }
+    }
+
     /// Returns the numeric FIPS code for this state.
+    #[must_use]
     pub fn encode(&self) -> StateCode {
-        *self as StateCode
+        // Every `USState` discriminant is a valid state code.
+        StateCode(*self as u8)
     }
 
     /// Returns the state for the given numeric FIPS code.
     /// Returns `Err(())` if the code is invalid.
-    pub fn decode(value: StateCode) -> Result<USState, ()> {
+    pub fn decode(value: u8) -> Result<USState, ()> {
         if !Self::is_state_code(value) {
             return Err(());
         }
         // Safety: The value is valid as checked by `is_state_code`.
-        Ok(unsafe { std::mem::transmute::<StateCode, USState>(value) })
+        Ok(unsafe { std::mem::transmute::<u8, USState>(value) })
+    }
+
+    /// Like `USState::decode`, but also recognizes the codes that early revisions of the FIPS 5 standard assigned to
+    /// outlying areas, which are the gaps in `VALID_STATE_CODES`:
+    ///
+    /// | Retired code | Area              | Current code |
+    /// |--------------|-------------------|--------------|
+    /// | 3            | American Samoa    | 60           |
+    /// | 7            | Panama Canal Zone | none         |
+    /// | 14           | Guam              | 66           |
+    /// | 43           | Puerto Rico       | 72           |
+    /// | 52           | Virgin Islands    | 78           |
+    ///
+    /// None of these areas is a `USState`, so a retired code is returned as `StateOrLegacy::Legacy` rather than
+    /// remapped to a state. Returns `FIPSCodeError::InvalidStateCode` for any other code that `USState::decode` rejects.
+    pub fn from_code_with_legacy(value: u8) -> Result<StateOrLegacy, FIPSCodeError> {
+        let modern = match value {
+            3 => Some(60),
+            7 => None,
+            14 => Some(66),
+            43 => Some(72),
+            52 => Some(78),
+            _ => {
+                return Self::decode(value)
+                    .map(StateOrLegacy::State)
+                    .map_err(|()| FIPSCodeError::InvalidStateCode { value })
+            }
+        };
+        Ok(StateOrLegacy::Legacy {
+            code: value,
+            modern: modern.map(StateCode),
+        })
     }
 }
 
@@ -96,6 +317,87 @@ impl From<USState> for StateCode {
     }
 }
 
+impl TryFrom<StateCode> for USState {
+    type Error = ();
+
+    /// Returns `Err(())` if `value` is not the code of a state or the District of Columbia, e.g. the code of an
+    /// outlying area.
+    fn try_from(value: StateCode) -> Result<Self, Self::Error> {
+        USState::decode(value.get())
+    }
+}
+
+/// A set of `USState`s stored as a bitmask with bit `n` set for the state with code `n`, which is possible because every
+/// state code is less than 64. Iteration is in increasing order of state code.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct StateSet(u64);
+
+impl StateSet {
+    /// Creates an empty `StateSet`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Returns the set of the states of `codes`, skipping codes whose state is not a `USState`.
+    pub fn from_codes(codes: impl IntoIterator<Item = FIPSCode>) -> Self {
+        codes
+            .into_iter()
+            .filter_map(|code| code.state().ok())
+            .collect()
+    }
+
+    /// Adds `state` to the set. Returns whether `state` was newly added.
+    pub fn insert(&mut self, state: USState) -> bool {
+        let newly_added = !self.contains(state);
+        self.0 |= 1 << state as u8;
+        newly_added
+    }
+
+    /// Returns `true` if the set contains `state`.
+    #[must_use]
+    pub const fn contains(&self, state: USState) -> bool {
+        self.0 & 1 << state as u8 != 0
+    }
+
+    /// Returns the number of states in the set.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns `true` if the set contains no states.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns an iterator over the states of the set in increasing order of state code.
+    pub fn iter(&self) -> impl Iterator<Item = USState> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let code = bits.trailing_zeros() as u8;
+            // Clear the lowest set bit.
+            bits &= bits - 1;
+            // Only the codes of `USState`s are ever inserted.
+            Some(USState::decode(code).unwrap())
+        })
+    }
+}
+
+impl FromIterator<USState> for StateSet {
+    fn from_iter<T: IntoIterator<Item = USState>>(states: T) -> Self {
+        let mut set = Self::new();
+        for state in states {
+            set.insert(state);
+        }
+        set
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,12 +407,119 @@ mod tests {
         assert_eq!(USState::AK.as_ref(), "AK");
     }
 
+    #[test]
+    fn test_from_code_with_legacy() {
+        assert_eq!(
+            USState::from_code_with_legacy(48),
+            Ok(StateOrLegacy::State(USState::TX))
+        );
+        // Puerto Rico's retired code maps to its current one.
+        assert_eq!(
+            USState::from_code_with_legacy(43),
+            Ok(StateOrLegacy::Legacy {
+                code: 43,
+                modern: StateCode::new(72),
+            })
+        );
+        assert_eq!(
+            USState::from_code_with_legacy(7),
+            Ok(StateOrLegacy::Legacy {
+                code: 7,
+                modern: None,
+            })
+        );
+        assert_eq!(
+            USState::from_code_with_legacy(0),
+            Err(FIPSCodeError::InvalidStateCode { value: 0 })
+        );
+        assert_eq!(
+            USState::from_code_with_legacy(72),
+            Err(FIPSCodeError::InvalidStateCode { value: 72 })
+        );
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(USState::TX.name(), "Texas");
+        assert_eq!(USState::DC.name(), "District of Columbia");
+        assert_eq!(USState::WV.name(), "West Virginia");
+    }
+
+    #[test]
+    fn test_time_zones() {
+        assert_eq!(
+            USState::TX.time_zones(),
+            &["America/Chicago", "America/Denver"]
+        );
+        assert_eq!(USState::HI.time_zones(), &["Pacific/Honolulu"]);
+        assert_eq!(USState::TN.time_zones().len(), 2);
+        assert_eq!(USState::FL.time_zones().len(), 2);
+        assert_eq!(USState::CO.time_zones(), &["America/Denver"]);
+    }
+
+    #[test]
+    fn test_state_set() {
+        let mut states = StateSet::new();
+        assert!(states.is_empty());
+        assert!(states.insert(USState::WY));
+        assert!(states.insert(USState::AL));
+        assert!(states.insert(USState::TX));
+        assert!(!states.insert(USState::AL));
+
+        assert_eq!(states.len(), 3);
+        assert!(states.contains(USState::TX));
+        assert!(!states.contains(USState::CA));
+        assert_eq!(
+            states.iter().collect::<Vec<_>>(),
+            vec![USState::AL, USState::TX, USState::WY]
+        );
+
+        let codes = [
+            FIPSCode::with_state(USState::TX),
+            FIPSCode::with_state_code(StateCode::new(72).unwrap()),
+            FIPSCode::with_county(USState::AL.into(), 1).unwrap(),
+        ];
+        assert_eq!(
+            StateSet::from_codes(codes),
+            StateSet::from_iter([USState::AL, USState::TX])
+        );
+    }
+
+    #[test]
+    fn test_county_count() {
+        assert_eq!(USState::TX.county_count(), 254);
+        assert_eq!(USState::DC.county_count(), 1);
+        assert_eq!(USState::VA.county_count(), 133);
+
+        let total: u32 = VALID_STATE_CODES
+            .iter()
+            .map(|&code| USState::decode(code).unwrap().county_count() as u32)
+            .sum();
+        assert_eq!(total, 3143);
+    }
+
     #[test]
     fn test_is_state() {
         assert!(USState::AK.is_state());
         assert!(USState::DC.is_state());
     }
 
+    #[test]
+    fn test_valid_state_codes() {
+        assert_eq!(VALID_STATE_CODES.len(), 51);
+        for code in 0..=63 {
+            assert_eq!(
+                USState::is_state_code(code),
+                VALID_STATE_CODES.contains(&code),
+                "state code {}",
+                code
+            );
+        }
+        for &code in VALID_STATE_CODES {
+            assert_eq!(USState::decode(code).unwrap().encode().get(), code);
+        }
+    }
+
     #[test]
     fn test_decode() {
         assert_eq!(USState::DC, USState::decode(11).unwrap());
@@ -126,7 +535,7 @@ mod tests {
 
     #[test]
     fn test_encode() {
-        assert_eq!(USState::DC.encode(), 11u8);
-        assert_eq!(USState::MN.encode(), 27u8);
+        assert_eq!(USState::DC.encode().get(), 11u8);
+        assert_eq!(USState::MN.encode().get(), 27u8);
     }
 }
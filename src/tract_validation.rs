@@ -0,0 +1,114 @@
+//! Validation of census tract codes against the list of tracts that actually exist. Enabled with the
+//! `tract_validation` feature.
+//!
+//! The bit-width checks performed when parsing or constructing a `FIPSCode` only guarantee that each field is
+//! representable, not that the (state, county, tract) triple names a real census tract. Because the full national
+//! tract list is large, it is not bundled with this crate. Instead, load the list of tracts relevant to your
+//! application, e.g. from the first column of the Census Bureau's tract relationship files, with
+//! `load_real_tracts_from_path` or `load_real_tracts`:
+//!
+//! ```
+//! # use ixa_fips::{tract_validation::{is_real_tract, load_real_tracts}, FIPSCode, USState};
+//! load_real_tracts("48201223100\n48201223200\n".as_bytes()).unwrap();
+//!
+//! let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+//! assert!(is_real_tract(tract));
+//! ```
+//!
+//! The input consists of one 11-digit census tract GEOID at the start of each line. Anything following the GEOID on a
+//! line, such as additional delimited columns, is ignored, as are blank lines and lines beginning with `#`.
+
+use crate::{parser::parse_tract_geoid, FIPSCode};
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::{BufRead, BufReader, Error as IoError, ErrorKind},
+    path::Path,
+    sync::RwLock,
+};
+
+// The tract-level codes of the real census tracts that have been loaded
+static REAL_TRACTS: RwLock<BTreeSet<FIPSCode>> = RwLock::new(BTreeSet::new());
+
+/// Returns `true` if the census tract containing `code` has been loaded as a real census tract. Fields below the
+/// census tract are ignored, so, for example, a home id is real if its tract is.
+#[must_use]
+pub fn is_real_tract(code: FIPSCode) -> bool {
+    REAL_TRACTS.read().unwrap().contains(&code.parent_tract())
+}
+
+/// Adds the census tracts listed in `reader` to the set of real census tracts. Returns the number of tracts read, or an
+/// error of kind `InvalidData` naming the line number of the first malformed line.
+pub fn load_real_tracts(reader: impl BufRead) -> Result<usize, IoError> {
+    let mut tracts = vec![];
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (_, tract) = parse_tract_geoid(line).ok_or_else(|| {
+            IoError::new(
+                ErrorKind::InvalidData,
+                format!("invalid census tract GEOID on line {}: {}", idx + 1, line),
+            )
+        })?;
+        tracts.push(tract);
+    }
+
+    let count = tracts.len();
+    REAL_TRACTS.write().unwrap().extend(tracts);
+    Ok(count)
+}
+
+/// Adds the census tracts listed in the file at `path` to the set of real census tracts. See `load_real_tracts`.
+pub fn load_real_tracts_from_path(path: impl AsRef<Path>) -> Result<usize, IoError> {
+    let file = File::open(path)?;
+    load_real_tracts(BufReader::new(file))
+}
+
+/// Forgets all loaded census tracts.
+pub fn clear_real_tracts() {
+    REAL_TRACTS.write().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::USState;
+
+    // A small subset of the census tracts in Harris County, TX and Autauga County, AL
+    const TRACT_SUBSET: &str = "\
+# GEOID|ALAND
+48201223100|1234
+48201223200|5678
+01001020100|9012
+";
+
+    // The set of real tracts is global, so everything is exercised in a single test.
+    #[test]
+    fn test_is_real_tract() {
+        clear_real_tracts();
+        assert_eq!(load_real_tracts(TRACT_SUBSET.as_bytes()).unwrap(), 3);
+
+        let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        assert!(is_real_tract(tract));
+        assert!(is_real_tract(tract.set_id(24).unwrap()));
+        assert!(is_real_tract(
+            FIPSCode::with_tract(USState::AL.into(), 1, 20100).unwrap()
+        ));
+
+        // Representable but not real
+        assert!(!is_real_tract(tract.set_tract(999_999).unwrap()));
+        assert!(!is_real_tract(tract.set_county(203).unwrap()));
+
+        // Malformed input is rejected without loading anything.
+        let error = load_real_tracts("48201223300\n4820122\n".as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(!is_real_tract(tract.set_tract(223_300).unwrap()));
+
+        clear_real_tracts();
+        assert!(!is_real_tract(tract));
+    }
+}
@@ -0,0 +1,146 @@
+//! The Census urban/rural classification of census tracts, for urban/rural stratification. Enabled with the
+//! `urban_rural` feature.
+//!
+//! The Census Bureau classifies blocks, not tracts, as urban or rural, so a tract-level classification is derived from
+//! a rule of the user's choosing, e.g. the majority of the tract's population. The classification is therefore not
+//! bundled with this crate. Instead, load a tract list with `load_urban_tracts_from_path` or `load_urban_tracts`:
+//!
+//! ```
+//! # use ixa_fips::{urban_rural::{is_urban_tract, load_urban_tracts}, FIPSCode, USState};
+//! load_urban_tracts("48201223100,U\n48201223200,R\n".as_bytes()).unwrap();
+//!
+//! let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+//! assert_eq!(is_urban_tract(tract), Some(true));
+//! ```
+//!
+//! Each line consists of an 11-digit census tract GEOID followed by a comma, a tab, or a `|` and the classification: `U`
+//! for urban or `R` for rural, in either case. Anything following the classification on a line is ignored, as are blank
+//! lines and lines beginning with `#`.
+
+use crate::{parser::parse_tract_geoid, FIPSCode};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufRead, BufReader, Error as IoError, ErrorKind},
+    path::Path,
+    sync::RwLock,
+};
+
+// The classification of each loaded census tract, keyed by tract-level code: `true` if urban
+static URBAN_TRACTS: RwLock<BTreeMap<FIPSCode, bool>> = RwLock::new(BTreeMap::new());
+
+/// Returns `Some(true)` if the census tract containing `code` is urban, `Some(false)` if it is rural, and `None` if its
+/// classification has not been loaded. Fields below the census tract are ignored, so, for example, a home id has the
+/// classification of its tract.
+#[must_use]
+pub fn is_urban_tract(code: FIPSCode) -> Option<bool> {
+    URBAN_TRACTS
+        .read()
+        .unwrap()
+        .get(&code.parent_tract())
+        .copied()
+}
+
+/// Adds the classifications listed in `reader`, replacing any already loaded for the same tracts. Returns the number of
+/// tracts read, or an error of kind `InvalidData` naming the line number of the first malformed line.
+pub fn load_urban_tracts(reader: impl BufRead) -> Result<usize, IoError> {
+    let mut classifications = vec![];
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let classification = parse_classification(line).ok_or_else(|| {
+            IoError::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "invalid urban/rural classification on line {}: {}",
+                    idx + 1,
+                    line
+                ),
+            )
+        })?;
+        classifications.push(classification);
+    }
+
+    let count = classifications.len();
+    URBAN_TRACTS.write().unwrap().extend(classifications);
+    Ok(count)
+}
+
+/// Adds the classifications listed in the file at `path`. See `load_urban_tracts`.
+pub fn load_urban_tracts_from_path(path: impl AsRef<Path>) -> Result<usize, IoError> {
+    let file = File::open(path)?;
+    load_urban_tracts(BufReader::new(file))
+}
+
+/// Forgets all loaded classifications.
+pub fn clear_urban_tracts() {
+    URBAN_TRACTS.write().unwrap().clear();
+}
+
+/// Parses a census tract GEOID and its classification separated by a delimiter at the start of `input`.
+fn parse_classification(input: &str) -> Option<(FIPSCode, bool)> {
+    let (rest, tract) = parse_tract_geoid(input)?;
+    let rest = rest.strip_prefix([',', '\t', '|'])?;
+    let mut chars = rest.chars();
+    let is_urban = match chars.next()?.to_ascii_uppercase() {
+        'U' => true,
+        'R' => false,
+        _ => return None,
+    };
+    // The classification is a single letter.
+    if chars.next().is_some_and(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some((tract, is_urban))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::USState;
+
+    // A small subset of tract classifications in Harris County, TX and Autauga County, AL
+    const CLASSIFICATION_SUBSET: &str = "\
+# GEOID|UR
+48201223100|U
+48201223200|u|majority urban population
+01001020100|R
+";
+
+    // The classifications are global, so everything is exercised in a single test.
+    #[test]
+    fn test_is_urban_tract() {
+        clear_urban_tracts();
+        assert_eq!(
+            load_urban_tracts(CLASSIFICATION_SUBSET.as_bytes()).unwrap(),
+            3
+        );
+
+        let tract = FIPSCode::with_tract(USState::TX.into(), 201, 223_100).unwrap();
+        assert_eq!(is_urban_tract(tract), Some(true));
+        assert_eq!(is_urban_tract(tract.set_id(24).unwrap()), Some(true));
+        assert_eq!(
+            is_urban_tract(tract.set_tract(223_200).unwrap()),
+            Some(true)
+        );
+        assert_eq!(
+            is_urban_tract(FIPSCode::with_tract(USState::AL.into(), 1, 20100).unwrap()),
+            Some(false)
+        );
+
+        // Not loaded
+        assert_eq!(is_urban_tract(tract.set_tract(999_999).unwrap()), None);
+
+        // Malformed input is rejected without loading anything.
+        let error = load_urban_tracts("48201223300|U\n48201223400|X\n".as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(is_urban_tract(tract.set_tract(223_300).unwrap()), None);
+
+        clear_urban_tracts();
+        assert_eq!(is_urban_tract(tract), None);
+    }
+}
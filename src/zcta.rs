@@ -0,0 +1,86 @@
+//! ZIP Code Tabulation Areas (ZCTAs), the Census Bureau's generalized areal representations of USPS ZIP Code service
+//! areas. A ZCTA is identified by a 5-digit code, e.g. `20746` for Suitland, MD. ZCTAs cross state and county lines, so
+//! they are not represented by `FIPSCode`. Parse a ZCTA with [`parse_zcta_code`](crate::parser::parse_zcta_code).
+//!
+//! `ZctaCode`s are ordered by their numeric value, so a sorted `Vec` of them can be binary searched and a `BTreeSet` of
+//! them can be range queried. They display as their zero-padded 5-digit GEOID.
+//!
+//! The `zcta_crosswalk` feature enables [`crate::zcta_crosswalk`], which relates ZCTAs to the counties they overlap.
+
+use std::fmt::{Display, Formatter};
+
+/// The largest 5-digit ZCTA code.
+const MAX_ZCTA: u32 = 99_999;
+
+/// A 5-digit ZCTA code.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+pub struct ZctaCode(u32);
+
+impl ZctaCode {
+    /// Returns the ZCTA with code `value`, or `None` if `value` has more than 5 digits.
+    #[must_use]
+    pub const fn new(value: u32) -> Option<Self> {
+        if value <= MAX_ZCTA {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the numeric value of the ZCTA code.
+    #[inline(always)]
+    #[must_use]
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the 5-digit GEOID of the ZCTA, zero padded. This is the same as its `Display` form.
+    #[must_use]
+    pub fn to_geoid_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for ZctaCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:05}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_sort_matches_numeric_order() {
+        let values = [20746, 601, 99_999, 10_001, 0];
+        let mut zctas: Vec<ZctaCode> = values.iter().map(|&v| ZctaCode::new(v).unwrap()).collect();
+        zctas.sort();
+
+        let mut sorted_values = values;
+        sorted_values.sort_unstable();
+        assert_eq!(
+            zctas.iter().map(|z| z.get()).collect::<Vec<_>>(),
+            sorted_values
+        );
+        assert!(zctas.binary_search(&ZctaCode::new(20746).unwrap()).is_ok());
+
+        // Range queries
+        let set: BTreeSet<ZctaCode> = zctas.into_iter().collect();
+        let range: Vec<ZctaCode> = set
+            .range(ZctaCode::new(600).unwrap()..ZctaCode::new(20_000).unwrap())
+            .copied()
+            .collect();
+        assert_eq!(
+            range,
+            [ZctaCode::new(601).unwrap(), ZctaCode::new(10_001).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(ZctaCode::new(601).unwrap().to_string(), "00601");
+        assert_eq!(ZctaCode::new(20746).unwrap().to_geoid_string(), "20746");
+    }
+}
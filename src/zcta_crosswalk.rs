@@ -0,0 +1,162 @@
+//! The ZCTA-to-county crosswalk, for joining ZIP-level data to county-level analyses. Enabled with the
+//! `zcta_crosswalk` feature.
+//!
+//! ZCTAs do not nest within counties: a ZCTA may overlap several counties. The relationship table is large, so it is
+//! not bundled with this crate. Instead, load it once, e.g. from the `GEOID_ZCTA5_20` and `GEOID_COUNTY_20` columns of
+//! the Census Bureau's ZCTA-to-county relationship file, with `load_zcta_crosswalk_from_path` or
+//! `load_zcta_crosswalk`:
+//!
+//! ```
+//! # use ixa_fips::{zcta::ZctaCode, zcta_crosswalk::{load_zcta_crosswalk, zcta_counties}};
+//! load_zcta_crosswalk("20746|24033\n".as_bytes()).unwrap();
+//!
+//! let counties = zcta_counties(ZctaCode::new(20746).unwrap());
+//! assert_eq!(counties.len(), 1);
+//! ```
+//!
+//! Each line consists of a 5-digit ZCTA followed by a 5-digit county GEOID, separated by a comma, a tab, or a `|`.
+//! Anything following the county GEOID on a line is ignored, as are blank lines, lines beginning with `#`, and a
+//! header line beginning with a letter.
+
+use crate::{
+    parser::{parse_county_code, parse_state_code, parse_zcta_code},
+    zcta::ZctaCode,
+    FIPSCode,
+};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, Error as IoError, ErrorKind},
+    path::Path,
+    sync::OnceLock,
+};
+
+// The county-level codes of the counties each ZCTA overlaps, in increasing order
+static ZCTA_COUNTIES: OnceLock<HashMap<ZctaCode, Box<[FIPSCode]>>> = OnceLock::new();
+
+/// Returns the county-level codes of the counties that `zcta` overlaps, in increasing order. Returns an empty slice if
+/// `zcta` is not in the crosswalk or no crosswalk has been loaded.
+#[must_use]
+pub fn zcta_counties(zcta: ZctaCode) -> &'static [FIPSCode] {
+    ZCTA_COUNTIES
+        .get()
+        .and_then(|crosswalk| crosswalk.get(&zcta))
+        .map_or(&[], |counties| counties)
+}
+
+/// Loads the ZCTA-to-county relationships listed in `reader` as the crosswalk. Returns the number of relationships read.
+/// Returns an error of kind `InvalidData` naming the line number of the first malformed line, or of kind
+/// `AlreadyExists` if a crosswalk has already been loaded, as the loaded crosswalk is never replaced.
+pub fn load_zcta_crosswalk(reader: impl BufRead) -> Result<usize, IoError> {
+    if ZCTA_COUNTIES.get().is_some() {
+        return Err(already_loaded());
+    }
+
+    let mut crosswalk: HashMap<ZctaCode, Vec<FIPSCode>> = HashMap::new();
+    let mut count = 0;
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            continue;
+        }
+        let (zcta, county) = parse_relationship(line).ok_or_else(|| {
+            IoError::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "invalid ZCTA-to-county relationship on line {}: {}",
+                    idx + 1,
+                    line
+                ),
+            )
+        })?;
+        crosswalk.entry(zcta).or_default().push(county);
+        count += 1;
+    }
+
+    let crosswalk = crosswalk
+        .into_iter()
+        .map(|(zcta, mut counties)| {
+            counties.sort_unstable();
+            counties.dedup();
+            (zcta, counties.into_boxed_slice())
+        })
+        .collect();
+    ZCTA_COUNTIES.set(crosswalk).map_err(|_| already_loaded())?;
+    Ok(count)
+}
+
+/// Loads the ZCTA-to-county relationships listed in the file at `path` as the crosswalk. See `load_zcta_crosswalk`.
+pub fn load_zcta_crosswalk_from_path(path: impl AsRef<Path>) -> Result<usize, IoError> {
+    let file = File::open(path)?;
+    load_zcta_crosswalk(BufReader::new(file))
+}
+
+fn already_loaded() -> IoError {
+    IoError::new(
+        ErrorKind::AlreadyExists,
+        "a ZCTA-to-county crosswalk has already been loaded",
+    )
+}
+
+/// Parses a 5-digit ZCTA and a 5-digit county GEOID separated by a delimiter at the start of `input`.
+fn parse_relationship(input: &str) -> Option<(ZctaCode, FIPSCode)> {
+    let (rest, zcta) = parse_zcta_code(input).ok()?;
+    let rest = rest.strip_prefix([',', '\t', '|'])?;
+    let (rest, state) = parse_state_code(rest).ok()?;
+    let (rest, county) = parse_county_code(rest).ok()?;
+    // The county GEOID must not run on into more digits.
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((zcta, FIPSCode::with_county(state, county).ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::USState;
+
+    // A small subset of the 2020 ZCTA-to-county relationships: Suitland, MD lies in Prince George's County, and ZCTA
+    // 20001 in DC; ZCTA 42223 straddles Christian County, KY and Montgomery County, TN.
+    const CROSSWALK_SUBSET: &str = "\
+GEOID_ZCTA5_20|GEOID_COUNTY_20
+20746|24033
+20001|11001
+42223|47125
+42223|21047
+";
+
+    // The crosswalk is global and loaded once, so everything is exercised in a single test.
+    #[test]
+    fn test_zcta_counties() {
+        let suitland = ZctaCode::new(20746).unwrap();
+        assert!(zcta_counties(suitland).is_empty());
+
+        // Malformed input is rejected without loading anything.
+        let error = load_zcta_crosswalk("20746|2403\n".as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        assert_eq!(load_zcta_crosswalk(CROSSWALK_SUBSET.as_bytes()).unwrap(), 4);
+
+        assert_eq!(
+            zcta_counties(suitland),
+            [FIPSCode::with_county(USState::MD.into(), 33).unwrap()]
+        );
+        assert_eq!(
+            zcta_counties(ZctaCode::new(42223).unwrap()),
+            [
+                FIPSCode::with_county(USState::KY.into(), 47).unwrap(),
+                FIPSCode::with_county(USState::TN.into(), 125).unwrap(),
+            ]
+        );
+        assert!(zcta_counties(ZctaCode::new(99999).unwrap()).is_empty());
+
+        let error = load_zcta_crosswalk(CROSSWALK_SUBSET.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::AlreadyExists);
+    }
+}